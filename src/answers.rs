@@ -0,0 +1,114 @@
+use std::fmt;
+use std::io;
+use std::path::Path;
+
+use crate::input::{self, InputError};
+use crate::solution::{Answer, Example, Part};
+
+/// Overrides the directory the known answers are looked up in.
+const ANSWERS_DIR_VAR: &str = "AOC_ANSWERS_DIR";
+const DEFAULT_ANSWERS_DIR: &str = "answers";
+
+/// The known-good answers of a day, read from `dayNN.txt` in the answers
+/// directory: the first line holds the answer of part 1, the second line the
+/// one of part 2. A missing file or an empty line means the answer is unknown.
+#[derive(Debug, Default, PartialEq, Eq)]
+pub struct KnownAnswers {
+    answers: [Option<String>; 2],
+}
+
+#[derive(Debug, PartialEq, Eq)]
+pub enum Verdict {
+    Pass,
+    Fail { expected: String },
+    Unknown,
+}
+
+impl fmt::Display for Verdict {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Verdict::Pass => write!(f, "pass"),
+            Verdict::Fail { expected } => write!(f, "fail, expected {expected}"),
+            Verdict::Unknown => write!(f, "unknown"),
+        }
+    }
+}
+
+impl KnownAnswers {
+    pub fn load(day: u32) -> Result<Self, InputError> {
+        let dir = input::dir_from_env(ANSWERS_DIR_VAR, DEFAULT_ANSWERS_DIR);
+        Self::load_from(&input::path_in(&dir, day))
+    }
+
+    pub fn of_example(example: &Example) -> Self {
+        KnownAnswers {
+            answers: [example.part1, example.part2].map(|answer| answer.map(String::from)),
+        }
+    }
+
+    fn load_from(path: &Path) -> Result<Self, InputError> {
+        match std::fs::read_to_string(path) {
+            Ok(text) => Ok(Self::parse(&text)),
+            Err(err) if err.kind() == io::ErrorKind::NotFound => Ok(Self::default()),
+            Err(source) => Err(InputError {
+                path: Some(path.to_path_buf()),
+                source,
+            }),
+        }
+    }
+
+    fn parse(text: &str) -> Self {
+        let mut lines = text
+            .lines()
+            .map(str::trim)
+            .map(|line| (!line.is_empty()).then(|| line.to_string()));
+
+        KnownAnswers {
+            answers: [lines.next().flatten(), lines.next().flatten()],
+        }
+    }
+
+    pub fn get(&self, part: Part) -> Option<&str> {
+        self.answers[part.number() as usize - 1].as_deref()
+    }
+
+    pub fn verify(&self, part: Part, answer: &Answer) -> Verdict {
+        match self.get(part) {
+            Some(expected) if expected == answer.to_string() => Verdict::Pass,
+            Some(expected) => Verdict::Fail {
+                expected: expected.to_string(),
+            },
+            None => Verdict::Unknown,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_verify() {
+        let known = KnownAnswers::parse("11\n");
+        assert_eq!(known.verify(Part::One, &Answer::Number(11)), Verdict::Pass);
+        assert_eq!(
+            known.verify(Part::Two, &Answer::Number(31)),
+            Verdict::Unknown
+        );
+
+        let known = KnownAnswers::parse("\n6,2\n");
+        assert_eq!(known.get(Part::One), None);
+        assert_eq!(
+            known.verify(Part::Two, &Answer::Text("6,3".to_string())),
+            Verdict::Fail {
+                expected: "6,2".to_string()
+            }
+        );
+    }
+
+    #[test]
+    fn test_missing_file() {
+        let known = KnownAnswers::load_from(Path::new("no/such/day05.txt")).unwrap();
+        assert_eq!(known, KnownAnswers::default());
+    }
+}
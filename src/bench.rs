@@ -0,0 +1,124 @@
+use std::fmt;
+use std::time::{Duration, Instant};
+
+use crate::parse::ParseError;
+use crate::solution::{Part, Solution};
+
+/// The spread of the durations measured over several iterations.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Stats {
+    pub min: Duration,
+    pub median: Duration,
+    pub max: Duration,
+}
+
+impl Stats {
+    /// Panics if there are no samples.
+    pub fn from_samples(mut samples: Vec<Duration>) -> Self {
+        assert!(!samples.is_empty(), "no samples to compute stats from");
+        samples.sort();
+
+        Stats {
+            min: samples[0],
+            median: samples[samples.len() / 2],
+            max: samples[samples.len() - 1],
+        }
+    }
+}
+
+/// How long a day takes to parse its input and to solve each part, a part
+/// that is not implemented or fails has no timings.
+#[derive(Debug)]
+pub struct Timings {
+    pub parse: Stats,
+    pub parts: Vec<(Part, Option<Stats>)>,
+}
+
+fn measure<T>(iterations: usize, mut f: impl FnMut() -> T) -> Stats {
+    let samples = (0..iterations)
+        .map(|_| {
+            let start = Instant::now();
+            std::hint::black_box(f());
+            start.elapsed()
+        })
+        .collect();
+
+    Stats::from_samples(samples)
+}
+
+/// Times parsing and both parts of a day, each one `iterations` times.
+pub fn bench<S: Solution>(input: &str, iterations: usize) -> Result<Timings, ParseError> {
+    let parsed = S::parse(input)?;
+    let parse = measure(iterations, || S::parse(input));
+
+    let parts = [Part::One, Part::Two]
+        .into_iter()
+        .map(|part| {
+            let solve = match part {
+                Part::One => S::try_part1,
+                Part::Two => S::try_part2,
+            };
+            let stats = match solve(&parsed) {
+                Ok(Some(_)) => Some(measure(iterations, || solve(&parsed))),
+                Ok(None) | Err(_) => None,
+            };
+            (part, stats)
+        })
+        .collect();
+
+    Ok(Timings { parse, parts })
+}
+
+/// A row of the table printed by `aoc24 bench`.
+pub struct Row {
+    pub day: u32,
+    pub step: String,
+    pub stats: Option<Stats>,
+}
+
+impl Row {
+    pub const HEADER: &'static str = "day  step    |        min |     median |        max";
+}
+
+impl fmt::Display for Row {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{:02}   {:<7} |", self.day, self.step)?;
+        match self.stats {
+            Some(stats) => write!(
+                f,
+                " {:>10.2?} | {:>10.2?} | {:>10.2?}",
+                stats.min, stats.median, stats.max
+            ),
+            None => write!(f, " {:>10} | {:>10} | {:>10}", "-", "-", "-"),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_stats() {
+        let samples = [5, 1, 3, 2, 4].map(Duration::from_millis).to_vec();
+        let stats = Stats::from_samples(samples);
+        assert_eq!(stats.min, Duration::from_millis(1));
+        assert_eq!(stats.median, Duration::from_millis(3));
+        assert_eq!(stats.max, Duration::from_millis(5));
+    }
+
+    #[test]
+    fn test_row() {
+        let stats = Stats::from_samples(vec![Duration::from_micros(1500)]);
+        let row = Row {
+            day: 7,
+            step: "part 2".to_string(),
+            stats: Some(stats),
+        };
+        assert_eq!(
+            row.to_string(),
+            "07   part 2  |     1.50ms |     1.50ms |     1.50ms"
+        );
+        assert_eq!(row.to_string().len(), Row::HEADER.len());
+    }
+}
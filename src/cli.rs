@@ -0,0 +1,725 @@
+use std::fmt;
+use std::ops::Bound;
+
+use crate::day07;
+use crate::input::Source;
+use crate::solution::Part;
+
+pub const USAGE: &str =
+    "usage: aoc24 run <days> [--part <1|2>] [--input <path>] [--verify] [--example]
+                [--format <text|json>]
+       aoc24 bench [<days>] [--iterations <n>]
+       aoc24 disassemble [--input <path>]
+       aoc24 trace [--input <path>] [--steps <n>]
+       aoc24 guard [--input <path>] [--loops]
+       aoc24 equations [--input <path>] [--operators <names>]
+       aoc24 antinodes [--input <path>] [--harmonics <range>]
+       aoc24 compact [--input <path>] [--whole-files] [--moves]
+
+<days> is a single day (7), a range (3..9, 3..=9) or `all`
+--input reads the input of a single day from <path>, `-` reads it from stdin;
+by default it is read from inputs/dayNN.txt, set AOC_INPUT_DIR to use another
+directory
+--verify compares the answers with the known ones in answers/dayNN.txt (first
+line part 1, second line part 2), set AOC_ANSWERS_DIR to use another directory
+--example runs the examples of the puzzle descriptions instead of the inputs and
+checks the answers given for them, parts an example has no answer for are skipped
+--format json prints a JSON array with the day, part, answer, duration and status
+of every part
+bench times parsing and each part over <n> iterations (default 10) for all days
+or just <days>
+disassemble prints the program of day 17 one instruction per line
+trace runs the program of day 17 and prints every executed instruction with the
+registers and output, stopping after <n> steps (default 10000)
+guard draws the path of the guard of day 6, with --loops once for every
+obstruction that makes her walk in a loop
+equations solves the equations of day 7 with the comma-separated <names> of
+add, mul, concat, sub, div, xor and pow (default add,mul,concat) and prints the
+solved ones and the total
+antinodes draws the antinodes of day 8 and counts them per frequency, for the
+harmonics in <range> like 1..=1 (default), 0.. or -2..3
+compact compacts the disk of day 9 block by block, or with --whole-files file
+by file, and prints its map, its blocks if all file ids are below 10 and a
+summary of the moves, --moves lists every move";
+
+const DEFAULT_OPERATORS: &str = "add,mul,concat";
+
+const DEFAULT_ITERATIONS: usize = 10;
+const DEFAULT_STEPS: usize = 10_000;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Format {
+    Text,
+    Json,
+}
+
+#[derive(Debug, PartialEq, Eq)]
+pub enum Command {
+    Run {
+        days: Vec<u32>,
+        part: Option<Part>,
+        input: Source,
+        verify: bool,
+        example: bool,
+        format: Format,
+    },
+    Bench {
+        days: Vec<u32>,
+        iterations: usize,
+    },
+    Disassemble {
+        input: Source,
+    },
+    Trace {
+        input: Source,
+        steps: usize,
+    },
+    Guard {
+        input: Source,
+        loops: bool,
+    },
+    Equations {
+        input: Source,
+        operators: Vec<&'static dyn day07::Operator>,
+    },
+    Antinodes {
+        input: Source,
+        harmonics: (Bound<i64>, Bound<i64>),
+    },
+    Compact {
+        input: Source,
+        whole_files: bool,
+        moves: bool,
+    },
+    Help,
+}
+
+#[derive(Debug, PartialEq, Eq)]
+pub struct CliError(String);
+
+impl fmt::Display for CliError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+fn parse_day(day: &str) -> Result<u32, CliError> {
+    let day: u32 = day
+        .parse()
+        .map_err(|_| CliError(format!("`{day}` is not a day number")))?;
+
+    if (1..=25).contains(&day) {
+        Ok(day)
+    } else {
+        Err(CliError(format!(
+            "day {day} is out of range, expected 1..=25"
+        )))
+    }
+}
+
+/// The exclusive end of a range of days, so one past the last day.
+fn parse_end(end: &str) -> Result<u32, CliError> {
+    let end: u32 = end
+        .parse()
+        .map_err(|_| CliError(format!("`{end}` is not a day number")))?;
+
+    if (1..=26).contains(&end) {
+        Ok(end)
+    } else {
+        Err(CliError(format!(
+            "`..{end}` is out of range, expected an end in 1..=26"
+        )))
+    }
+}
+
+fn parse_days(days: &str) -> Result<Vec<u32>, CliError> {
+    if days == "all" {
+        return Ok((1..=25).collect());
+    }
+
+    let range = if let Some((first, last)) = days.split_once("..=") {
+        parse_day(first)?..=parse_day(last)?
+    } else if let Some((first, end)) = days.split_once("..") {
+        let end = parse_end(end)?;
+        parse_day(first)?..=end - 1
+    } else {
+        let day = parse_day(days)?;
+        day..=day
+    };
+
+    if range.is_empty() {
+        return Err(CliError(format!("`{days}` does not contain any day")));
+    }
+
+    Ok(range.collect())
+}
+
+fn parse_part(part: &str) -> Result<Part, CliError> {
+    match part {
+        "1" => Ok(Part::One),
+        "2" => Ok(Part::Two),
+        _ => Err(CliError(format!("`{part}` is not a part, expected 1 or 2"))),
+    }
+}
+
+fn parse_format(format: &str) -> Result<Format, CliError> {
+    match format {
+        "text" => Ok(Format::Text),
+        "json" => Ok(Format::Json),
+        _ => Err(CliError(format!(
+            "`{format}` is not a format, expected text or json"
+        ))),
+    }
+}
+
+fn parse_operators(names: &str) -> Result<Vec<&'static dyn day07::Operator>, CliError> {
+    names
+        .split(',')
+        .map(|name| {
+            day07::operator(name).ok_or_else(|| CliError(format!("`{name}` is not an operator")))
+        })
+        .collect()
+}
+
+/// A range of harmonics like `1..=1`, `0..` or `-2..3`, a single number is
+/// just that harmonic.
+fn parse_harmonics(range: &str) -> Result<(Bound<i64>, Bound<i64>), CliError> {
+    let invalid = || CliError(format!("`{range}` is not a range of harmonics"));
+    let bound = |number: &str, bound: fn(i64) -> Bound<i64>| match number {
+        "" => Ok(Bound::Unbounded),
+        _ => number.parse().map(bound).map_err(|_| invalid()),
+    };
+
+    if let Some((start, end)) = range.split_once("..=") {
+        if end.is_empty() {
+            return Err(invalid());
+        }
+        Ok((bound(start, Bound::Included)?, bound(end, Bound::Included)?))
+    } else if let Some((start, end)) = range.split_once("..") {
+        Ok((bound(start, Bound::Included)?, bound(end, Bound::Excluded)?))
+    } else {
+        let harmonic = range.parse().map_err(|_| invalid())?;
+        Ok((Bound::Included(harmonic), Bound::Included(harmonic)))
+    }
+}
+
+fn parse_count(count: &str, what: &str) -> Result<usize, CliError> {
+    match count.parse() {
+        Ok(0) | Err(_) => Err(CliError(format!(
+            "`{count}` is not a positive number of {what}"
+        ))),
+        Ok(count) => Ok(count),
+    }
+}
+
+/// The source named by the value of `--input`, which `args` yields next.
+fn parse_input_flag(args: &mut impl Iterator<Item = String>) -> Result<Source, CliError> {
+    let value = args
+        .next()
+        .ok_or_else(|| CliError("`--input` needs a path, or `-` for stdin".to_string()))?;
+    Ok(Source::from_arg(&value))
+}
+
+pub fn parse_args(args: impl IntoIterator<Item = String>) -> Result<Command, CliError> {
+    let mut args = args.into_iter();
+
+    match args.next().as_deref() {
+        Some("run") => parse_run(args),
+        Some("bench") => parse_bench(args),
+        Some("disassemble") => parse_disassemble(args),
+        Some("trace") => parse_trace(args),
+        Some("guard") => parse_guard(args),
+        Some("equations") => parse_equations(args),
+        Some("antinodes") => parse_antinodes(args),
+        Some("compact") => parse_compact(args),
+        Some("help" | "-h" | "--help") => Ok(Command::Help),
+        Some(other) => Err(CliError(format!("unknown command `{other}`"))),
+        None => Err(CliError("missing command".to_string())),
+    }
+}
+
+fn parse_run(mut args: impl Iterator<Item = String>) -> Result<Command, CliError> {
+    let mut days = None;
+    let mut part = None;
+    let mut input = Source::InputDir;
+    let mut verify = false;
+    let mut example = false;
+    let mut format = Format::Text;
+
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--part" | "-p" => {
+                let value = args
+                    .next()
+                    .ok_or_else(|| CliError("`--part` needs a value".to_string()))?;
+                part = Some(parse_part(&value)?);
+            }
+            "--input" | "-i" => input = parse_input_flag(&mut args)?,
+            "--verify" | "-v" => verify = true,
+            "--example" | "-e" => example = true,
+            "--format" | "-f" => {
+                let value = args
+                    .next()
+                    .ok_or_else(|| CliError("`--format` needs a value".to_string()))?;
+                format = parse_format(&value)?;
+            }
+            _ if days.is_none() => days = Some(parse_days(&arg)?),
+            _ => return Err(CliError(format!("unexpected argument `{arg}`"))),
+        }
+    }
+
+    let days = days.ok_or_else(|| CliError("missing days to run".to_string()))?;
+    if input != Source::InputDir && days.len() > 1 {
+        return Err(CliError(
+            "`--input` can only be used when running a single day".to_string(),
+        ));
+    }
+
+    if input != Source::InputDir && verify {
+        return Err(CliError(
+            "`--verify` cannot be combined with `--input`".to_string(),
+        ));
+    }
+
+    if input != Source::InputDir && example {
+        return Err(CliError(
+            "`--example` cannot be combined with `--input`".to_string(),
+        ));
+    }
+
+    Ok(Command::Run {
+        days,
+        part,
+        input,
+        verify,
+        example,
+        format,
+    })
+}
+
+fn parse_bench(mut args: impl Iterator<Item = String>) -> Result<Command, CliError> {
+    let mut days = None;
+    let mut iterations = DEFAULT_ITERATIONS;
+
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--iterations" | "-n" => {
+                let value = args
+                    .next()
+                    .ok_or_else(|| CliError("`--iterations` needs a value".to_string()))?;
+                iterations = parse_count(&value, "iterations")?;
+            }
+            _ if days.is_none() => days = Some(parse_days(&arg)?),
+            _ => return Err(CliError(format!("unexpected argument `{arg}`"))),
+        }
+    }
+
+    Ok(Command::Bench {
+        days: days.unwrap_or_else(|| (1..=25).collect()),
+        iterations,
+    })
+}
+
+fn parse_disassemble(mut args: impl Iterator<Item = String>) -> Result<Command, CliError> {
+    let mut input = Source::InputDir;
+
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--input" | "-i" => input = parse_input_flag(&mut args)?,
+            _ => return Err(CliError(format!("unexpected argument `{arg}`"))),
+        }
+    }
+
+    Ok(Command::Disassemble { input })
+}
+
+fn parse_trace(mut args: impl Iterator<Item = String>) -> Result<Command, CliError> {
+    let mut input = Source::InputDir;
+    let mut steps = DEFAULT_STEPS;
+
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--input" | "-i" => input = parse_input_flag(&mut args)?,
+            "--steps" | "-n" => {
+                let value = args
+                    .next()
+                    .ok_or_else(|| CliError("`--steps` needs a value".to_string()))?;
+                steps = parse_count(&value, "steps")?;
+            }
+            _ => return Err(CliError(format!("unexpected argument `{arg}`"))),
+        }
+    }
+
+    Ok(Command::Trace { input, steps })
+}
+
+fn parse_guard(mut args: impl Iterator<Item = String>) -> Result<Command, CliError> {
+    let mut input = Source::InputDir;
+    let mut loops = false;
+
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--input" | "-i" => input = parse_input_flag(&mut args)?,
+            "--loops" | "-l" => loops = true,
+            _ => return Err(CliError(format!("unexpected argument `{arg}`"))),
+        }
+    }
+
+    Ok(Command::Guard { input, loops })
+}
+
+fn parse_antinodes(mut args: impl Iterator<Item = String>) -> Result<Command, CliError> {
+    let mut input = Source::InputDir;
+    let mut harmonics = (Bound::Included(1), Bound::Included(1));
+
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--input" | "-i" => input = parse_input_flag(&mut args)?,
+            "--harmonics" | "-k" => {
+                let value = args
+                    .next()
+                    .ok_or_else(|| CliError("`--harmonics` needs a value".to_string()))?;
+                harmonics = parse_harmonics(&value)?;
+            }
+            _ => return Err(CliError(format!("unexpected argument `{arg}`"))),
+        }
+    }
+
+    Ok(Command::Antinodes { input, harmonics })
+}
+
+fn parse_compact(mut args: impl Iterator<Item = String>) -> Result<Command, CliError> {
+    let mut input = Source::InputDir;
+    let mut whole_files = false;
+    let mut moves = false;
+
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--input" | "-i" => input = parse_input_flag(&mut args)?,
+            "--whole-files" | "-w" => whole_files = true,
+            "--moves" | "-m" => moves = true,
+            _ => return Err(CliError(format!("unexpected argument `{arg}`"))),
+        }
+    }
+
+    Ok(Command::Compact {
+        input,
+        whole_files,
+        moves,
+    })
+}
+
+fn parse_equations(mut args: impl Iterator<Item = String>) -> Result<Command, CliError> {
+    let mut input = Source::InputDir;
+    let mut operators = None;
+
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--input" | "-i" => input = parse_input_flag(&mut args)?,
+            "--operators" | "-o" => {
+                let value = args
+                    .next()
+                    .ok_or_else(|| CliError("`--operators` needs a value".to_string()))?;
+                operators = Some(parse_operators(&value)?);
+            }
+            _ => return Err(CliError(format!("unexpected argument `{arg}`"))),
+        }
+    }
+
+    Ok(Command::Equations {
+        input,
+        operators: match operators {
+            Some(operators) => operators,
+            None => parse_operators(DEFAULT_OPERATORS)?,
+        },
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::day07::Operator;
+
+    fn parse(args: &str) -> Result<Command, CliError> {
+        parse_args(args.split_whitespace().map(String::from))
+    }
+
+    #[test]
+    fn test_single_day() {
+        assert_eq!(
+            parse("run 7 --part 2"),
+            Ok(Command::Run {
+                days: vec![7],
+                part: Some(Part::Two),
+                input: Source::InputDir,
+                verify: false,
+                example: false,
+                format: Format::Text
+            })
+        );
+    }
+
+    #[test]
+    fn test_ranges() {
+        assert_eq!(
+            parse("run 3..6"),
+            Ok(Command::Run {
+                days: vec![3, 4, 5],
+                part: None,
+                input: Source::InputDir,
+                verify: false,
+                example: false,
+                format: Format::Text
+            })
+        );
+        assert_eq!(
+            parse("run 3..=6"),
+            Ok(Command::Run {
+                days: vec![3, 4, 5, 6],
+                part: None,
+                input: Source::InputDir,
+                verify: false,
+                example: false,
+                format: Format::Text
+            })
+        );
+        assert_eq!(
+            parse("run all"),
+            Ok(Command::Run {
+                days: (1..=25).collect(),
+                part: None,
+                input: Source::InputDir,
+                verify: false,
+                example: false,
+                format: Format::Text
+            })
+        );
+    }
+
+    #[test]
+    fn test_input() {
+        assert_eq!(
+            parse("run 5 --input alt/day05.txt"),
+            Ok(Command::Run {
+                days: vec![5],
+                part: None,
+                input: Source::File("alt/day05.txt".into()),
+                verify: false,
+                example: false,
+                format: Format::Text
+            })
+        );
+        assert_eq!(
+            parse("run -i - 5 -p 1"),
+            Ok(Command::Run {
+                days: vec![5],
+                part: Some(Part::One),
+                input: Source::Stdin,
+                verify: false,
+                example: false,
+                format: Format::Text
+            })
+        );
+    }
+
+    #[test]
+    fn test_verify() {
+        assert_eq!(
+            parse("run all --verify"),
+            Ok(Command::Run {
+                days: (1..=25).collect(),
+                part: None,
+                input: Source::InputDir,
+                verify: true,
+                example: false,
+                format: Format::Text
+            })
+        );
+        assert!(parse("run 5 --verify --input -").is_err());
+    }
+
+    #[test]
+    fn test_format() {
+        assert_eq!(
+            parse("run 5 --format json"),
+            Ok(Command::Run {
+                days: vec![5],
+                part: None,
+                input: Source::InputDir,
+                verify: false,
+                example: false,
+                format: Format::Json
+            })
+        );
+        assert!(parse("run 5 --format yaml").is_err());
+    }
+
+    #[test]
+    fn test_example() {
+        assert_eq!(
+            parse("run 6 --example"),
+            Ok(Command::Run {
+                days: vec![6],
+                part: None,
+                input: Source::InputDir,
+                verify: false,
+                example: true,
+                format: Format::Text
+            })
+        );
+        assert!(parse("run 6 --example --input day06.txt").is_err());
+    }
+
+    #[test]
+    fn test_bench() {
+        assert_eq!(
+            parse("bench"),
+            Ok(Command::Bench {
+                days: (1..=25).collect(),
+                iterations: DEFAULT_ITERATIONS
+            })
+        );
+        assert_eq!(
+            parse("bench 6 -n 3"),
+            Ok(Command::Bench {
+                days: vec![6],
+                iterations: 3
+            })
+        );
+        assert!(parse("bench 6 --iterations 0").is_err());
+        assert!(parse("bench 6 --part 1").is_err());
+    }
+
+    #[test]
+    fn test_disassemble() {
+        assert_eq!(
+            parse("disassemble"),
+            Ok(Command::Disassemble {
+                input: Source::InputDir
+            })
+        );
+        assert_eq!(
+            parse("disassemble -i -"),
+            Ok(Command::Disassemble {
+                input: Source::Stdin
+            })
+        );
+        assert!(parse("disassemble 17").is_err());
+        assert_eq!(
+            parse("disassemble --input"),
+            Err(CliError(
+                "`--input` needs a path, or `-` for stdin".to_string()
+            ))
+        );
+    }
+
+    #[test]
+    fn test_trace() {
+        assert_eq!(
+            parse("trace --steps 50"),
+            Ok(Command::Trace {
+                input: Source::InputDir,
+                steps: 50
+            })
+        );
+        assert!(parse("trace --steps -1").is_err());
+    }
+
+    #[test]
+    fn test_guard() {
+        assert_eq!(
+            parse("guard --loops -i day06.txt"),
+            Ok(Command::Guard {
+                input: Source::File("day06.txt".into()),
+                loops: true
+            })
+        );
+        assert!(parse("guard 6").is_err());
+    }
+
+    #[test]
+    fn test_equations() {
+        assert_eq!(
+            parse("equations"),
+            Ok(Command::Equations {
+                input: Source::InputDir,
+                operators: vec![
+                    &day07::Add as &dyn Operator,
+                    &day07::Multiply,
+                    &day07::Concat
+                ]
+            })
+        );
+        assert_eq!(
+            parse("equations -o sub,xor"),
+            Ok(Command::Equations {
+                input: Source::InputDir,
+                operators: vec![&day07::Subtract as &dyn Operator, &day07::Xor]
+            })
+        );
+        assert!(parse("equations --operators add,mod").is_err());
+    }
+
+    #[test]
+    fn test_antinodes() {
+        assert_eq!(
+            parse("antinodes"),
+            Ok(Command::Antinodes {
+                input: Source::InputDir,
+                harmonics: (Bound::Included(1), Bound::Included(1))
+            })
+        );
+        assert_eq!(
+            parse_harmonics("0.."),
+            Ok((Bound::Included(0), Bound::Unbounded))
+        );
+        assert_eq!(
+            parse_harmonics("-2..3"),
+            Ok((Bound::Included(-2), Bound::Excluded(3)))
+        );
+        assert_eq!(
+            parse_harmonics("..=4"),
+            Ok((Bound::Unbounded, Bound::Included(4)))
+        );
+        assert_eq!(
+            parse_harmonics("2"),
+            Ok((Bound::Included(2), Bound::Included(2)))
+        );
+        assert!(parse_harmonics("1..=").is_err());
+        assert!(parse_harmonics("a..b").is_err());
+    }
+
+    #[test]
+    fn test_compact() {
+        assert_eq!(
+            parse("compact -w --moves"),
+            Ok(Command::Compact {
+                input: Source::InputDir,
+                whole_files: true,
+                moves: true
+            })
+        );
+        assert!(parse("compact 9").is_err());
+    }
+
+    #[test]
+    fn test_days() {
+        assert_eq!(parse_days("1..26"), Ok((1..=25).collect()));
+        assert_eq!(parse_days("25..26"), Ok(vec![25]));
+        assert_eq!(parse_days("all"), parse_days("1..=25"));
+    }
+
+    #[test]
+    fn test_invalid() {
+        assert!(parse("run 0").is_err());
+        assert!(parse("run 26").is_err());
+        assert!(parse("run 9..3").is_err());
+        assert!(parse("run 3..3").is_err());
+        assert!(parse("run 3..27").is_err());
+        assert!(parse("run 7 --part 3").is_err());
+        assert!(parse("run").is_err());
+        assert!(parse("walk 7").is_err());
+        assert!(parse("run 7 --input").is_err());
+        assert!(parse("run 3..6 --input -").is_err());
+    }
+}
@@ -1,50 +1,112 @@
-use itertools::Itertools;
+use std::collections::HashMap;
+use std::fmt;
+use std::io::{self, BufRead};
 
-#[derive(Debug, PartialEq, Eq)]
-struct Locations {
+use crate::parse::{self, ParseError};
+use crate::solution::{Answer, Example, Solution};
+
+#[derive(Debug, Default, PartialEq, Eq)]
+pub struct Locations {
     left: Vec<u32>,
     right: Vec<u32>,
 }
 
-impl Locations{
-    fn sort_and_abs_diff(&self) -> u32 {
-        let left_sorted = self.left.iter().sorted();
-        let right_sorted = self.right.iter().sorted();
-        std::iter::zip(left_sorted, right_sorted).map(|(&l, &r)| l.abs_diff(r)).sum()
-    }
-
-    fn calculate_similarity_score(&self) -> u32 {
-        self.left.iter().map(|&x| self.calculate_frequency_in_right(x)*x).sum()
-    }
+/// Why the locations could not be read.
+#[derive(Debug)]
+pub enum ReadError {
+    /// Reading line `line` failed, e.g. because it is not valid UTF-8.
+    Io {
+        line: usize,
+        error: io::Error,
+    },
+    Parse(ParseError),
+}
 
-    fn calculate_frequency_in_right(&self, value: u32) -> u32 {
-        self.right.iter().filter(|&&x| x == value).count() as u32
+impl fmt::Display for ReadError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ReadError::Io { line, error } => write!(f, "could not read line {line}: {error}"),
+            ReadError::Parse(err) => write!(f, "{err}"),
+        }
     }
 }
 
+impl Locations {
+    /// Reads the lists line by line, so only the numbers are kept in memory
+    /// and never the whole text.
+    pub fn read(mut reader: impl BufRead) -> Result<Locations, ReadError> {
+        let mut locations = Locations::default();
+        let mut line = String::new();
+
+        for number in 1.. {
+            line.clear();
+            let read = reader.read_line(&mut line).map_err(|error| ReadError::Io {
+                line: number,
+                error,
+            })?;
+            if read == 0 {
+                break;
+            }
+
+            let (left, right) = parse_line(line.trim_end_matches(['\n', '\r']))
+                .map_err(|err| ReadError::Parse(err.on_line(number)))?;
+            locations.left.push(left);
+            locations.right.push(right);
+        }
+
+        Ok(locations)
+    }
 
-fn parse(input: &str) -> Locations {
-    let (left, right): (Vec<_>, Vec<_>) = input.lines()
-        .map(|line| {
-            let mut parts = line.split_whitespace();
-            let first_number : u32 = parts.next().unwrap().parse().unwrap();
-            let second_number : u32 = parts.next().unwrap().parse().unwrap();
-            (first_number, second_number)
-        })
-        .unzip();
+    fn sort_and_abs_diff(&self) -> u64 {
+        let mut left = self.left.clone();
+        let mut right = self.right.clone();
+        left.sort_unstable();
+        right.sort_unstable();
+        std::iter::zip(left, right)
+            .map(|(l, r)| u64::from(l.abs_diff(r)))
+            .sum()
+    }
 
-    Locations { left, right }
+    fn calculate_similarity_score(&self) -> u64 {
+        let right = frequencies(&self.right);
+        frequencies(&self.left)
+            .into_iter()
+            .map(|(value, count)| {
+                u64::from(value) * count * right.get(&value).copied().unwrap_or(0)
+            })
+            .sum()
+    }
 }
 
+/// How often each value occurs.
+fn frequencies(values: &[u32]) -> HashMap<u32, u64> {
+    let mut frequencies = HashMap::new();
+    for &value in values {
+        *frequencies.entry(value).or_insert(0) += 1;
+    }
+    frequencies
+}
 
-pub fn solve(input: &str) {
-    println!("{}", parse(input).sort_and_abs_diff());
-    println!("{}", parse(input).calculate_similarity_score());
+fn parse_line(line: &str) -> Result<(u32, u32), ParseError> {
+    let mut parts = line.split_whitespace();
+    let mut next_number = || match parts.next() {
+        Some(part) => parse::number::<u32>(line, part),
+        None => Err(ParseError::after(line, line, "expected two numbers")),
+    };
+    let first_number = next_number()?;
+    let second_number = next_number()?;
+    if let Some(extra) = parts.next() {
+        return Err(ParseError::new(line, extra, "expected only two numbers"));
+    }
+    Ok((first_number, second_number))
 }
 
-#[cfg(test)]
-mod tests {
-    use super::*;
+fn parse(input: &str) -> Result<Locations, ParseError> {
+    Locations::read(input.as_bytes()).map_err(|err| match err {
+        ReadError::Parse(err) => err,
+        ReadError::Io { .. } => unreachable!("a `str` is always valid UTF-8"),
+    })
+}
 
 const EXAMPLE: &str = "3   4
 4   3
@@ -53,6 +115,34 @@ const EXAMPLE: &str = "3   4
 3   9
 3   3";
 
+pub struct Day01;
+
+impl Solution for Day01 {
+    type Input = Locations;
+
+    const EXAMPLES: &'static [Example] = &[Example {
+        input: EXAMPLE,
+        part1: Some("11"),
+        part2: Some("31"),
+    }];
+
+    fn parse(input: &str) -> Result<Locations, ParseError> {
+        parse(input)
+    }
+
+    fn part1(locations: &Locations) -> Option<Answer> {
+        Some(locations.sort_and_abs_diff().into())
+    }
+
+    fn part2(locations: &Locations) -> Option<Answer> {
+        Some(locations.calculate_similarity_score().into())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
     fn example_locations() -> Locations {
         Locations {
             left: vec![3, 4, 2, 1, 3, 3],
@@ -60,24 +150,69 @@ const EXAMPLE: &str = "3   4
         }
     }
 
-
     #[test]
     fn parse_example() {
-        assert_eq!(parse(EXAMPLE), example_locations());
+        assert_eq!(parse(EXAMPLE), Ok(example_locations()));
+    }
+
+    #[test]
+    fn parse_invalid() {
+        let err = parse("3   4\n4   x\n2").unwrap_err();
+        assert_eq!((err.line, err.column, err.text.as_str()), (2, 5, "x"));
+        assert_eq!(parse("3   4\n2").unwrap_err().line, 2);
+        let err = parse("3   4\n4   3 5").unwrap_err();
+        assert_eq!((err.line, err.column, err.text.as_str()), (2, 7, "5"));
     }
-    
+
     #[test]
     fn test_sort_and_abs_diff() {
         assert_eq!(example_locations().sort_and_abs_diff(), 11);
     }
 
     #[test]
-    fn test_frequency_in_right() {
-        assert_eq!(example_locations().calculate_frequency_in_right(3), 3);
+    fn test_frequencies() {
+        assert_eq!(frequencies(&example_locations().right)[&3], 3);
+        assert_eq!(frequencies(&example_locations().left).get(&5), None);
     }
 
     #[test]
     fn test_similarity_score() {
         assert_eq!(example_locations().calculate_similarity_score(), 31);
     }
+
+    // every value of 0..1000 occurs 1000 times on the left, every value of
+    // 1..=1000 as often on the right
+    fn large_input() -> String {
+        (0..1_000_000u32)
+            .map(|i| format!("{}   {}\n", i % 1000, (i * 7 + 3) % 1000 + 1))
+            .collect()
+    }
+
+    #[test]
+    fn test_large_input() {
+        let input = large_input();
+        let locations = Locations::read(std::io::Cursor::new(input)).unwrap();
+        assert_eq!(locations.left.len(), 1_000_000);
+        assert_eq!(locations.sort_and_abs_diff(), 1_000_000);
+        assert_eq!(
+            locations.calculate_similarity_score(),
+            1_000_000 * (1..1000).sum::<u64>()
+        );
+    }
+
+    #[test]
+    fn read_invalid() {
+        let mut input = large_input();
+        input.push_str("12   x\n");
+        let Err(ReadError::Parse(err)) = Locations::read(input.as_bytes()) else {
+            panic!("expected a parse error");
+        };
+        assert_eq!(
+            (err.line, err.column, err.text.as_str()),
+            (1_000_001, 6, "x")
+        );
+
+        let err = Locations::read(&b"1   2\n\xff   3\n"[..]).unwrap_err();
+        assert!(matches!(err, ReadError::Io { line: 2, .. }), "{err}");
+    }
 }
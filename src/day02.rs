@@ -1,58 +1,157 @@
 
+use std::fmt;
+use std::ops::RangeInclusive;
+
 use itertools::Itertools;
 
+use crate::parse::{self, ParseError};
+use crate::solution::{Answer, Example, Solution};
+
 #[derive(Debug, PartialEq, Eq)]
-struct Report {
+pub struct Report {
     levels: Vec<u32>,
 }
 
-impl Report {
-    fn get_adj_difference(&self) -> Vec<u32> {
-        self.levels.iter().tuple_windows().map(|(a, b)| a.abs_diff(*b) ).collect()
+/// What makes a report safe.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Rules {
+    /// The allowed difference between two adjacent levels.
+    pub delta: RangeInclusive<u32>,
+    /// How many levels may be removed to make a report safe.
+    pub tolerated: usize,
+}
+
+impl Rules {
+    pub const STRICT: Rules = Rules { delta: RangeInclusive::new(1, 3), tolerated: 0 };
+    pub const DAMPENED: Rules = Rules { delta: RangeInclusive::new(1, 3), tolerated: 1 };
+
+    fn allows(&self, from: u32, to: u32, increasing: bool) -> bool {
+        (if increasing { from <= to } else { from >= to })
+        && self.delta.contains(&from.abs_diff(to))
     }
+}
 
-    fn is_strictly_monoton(&self) -> bool {
-        self.levels.iter().tuple_windows().all(|(a, b)| a < b) || 
-        self.levels.iter().tuple_windows().all(|(a, b)| a > b)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Problem {
+    /// The step from the previous level is not in the allowed range.
+    Step { delta: u32 },
+    /// The level goes the other way than the levels before it.
+    Direction,
+}
+
+/// The first level of a report that breaks the rules.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Unsafe {
+    pub index: usize,
+    pub problem: Problem,
+}
+
+impl fmt::Display for Unsafe {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.problem {
+            Problem::Step { delta } => write!(f, "level {} changes by {delta}", self.index),
+            Problem::Direction => write!(f, "level {} changes direction", self.index),
+        }
     }
+}
 
-    fn is_safe(&self) -> bool {
-        self.is_strictly_monoton() && 
-        self.get_adj_difference().iter().all(|&x| (1..=3).contains(&x))
+impl Report {
+    pub fn is_safe(&self, rules: &Rules) -> bool {
+        self.explain(rules).is_none()
     }
 
-    fn is_safe_more_tolerant(&self) -> bool {
-        (0..self.levels.len()).any(|index_to_ignore|{
-            let (left, right) = self.levels.split_at(index_to_ignore);
-            let levels = left.iter().chain(right.iter().skip(1)).copied().collect();
-            (Report { levels }).is_safe()
+    /// Why the report is unsafe, `None` if it is safe. The problem is the
+    /// first one of all levels, even when the rules tolerate some of them.
+    pub fn explain(&self, rules: &Rules) -> Option<Unsafe> {
+        if self.min_removals(rules, true).min(self.min_removals(rules, false)) <= rules.tolerated {
+            return None;
+        }
+
+        let mut increasing = None;
+        self.levels.iter().tuple_windows().enumerate().find_map(|(index, (&from, &to))| {
+            let index = index + 1;
+            if !rules.delta.contains(&from.abs_diff(to)) {
+                return Some(Unsafe { index, problem: Problem::Step { delta: from.abs_diff(to) } });
+            }
+            if from == to {
+                return None;
+            }
+            match increasing {
+                Some(increasing) if increasing != (from < to) => Some(Unsafe { index, problem: Problem::Direction }),
+                _ => {
+                    increasing = Some(from < to);
+                    None
+                }
+            }
         })
     }
-}
 
-fn solve_1(reports : Vec<Report>) -> u32 {
-    // count the number of reports that are safe
-    reports.iter().filter(|report| report.is_safe()).count() as u32
-}
+    /// The fewest levels to remove so that the rest goes in one direction
+    /// by allowed steps. Only gaps up to `rules.tolerated` levels are tried,
+    /// which keeps this linear for a fixed tolerance.
+    fn min_removals(&self, rules: &Rules, increasing: bool) -> usize {
+        let levels = &self.levels;
+        // removals[i]: the fewest removals among levels[..=i] if levels[i] is kept
+        let mut removals = Vec::with_capacity(levels.len());
+        for (i, &level) in levels.iter().enumerate() {
+            let kept = (i.saturating_sub(rules.tolerated + 1)..i)
+                .filter(|&j| rules.allows(levels[j], level, increasing))
+                .map(|j| removals[j] + (i - j - 1))
+                .min();
+            removals.push(kept.map_or(i, |kept: usize| kept.min(i)));
+        }
 
-fn solve_2(reports : Vec<Report>) -> u32 {
-    // count again, but be more tolerant
-    reports.iter().filter(|report| report.is_safe_more_tolerant()).count() as u32
+        removals.iter().enumerate()
+            .map(|(i, removed)| removed + (levels.len() - 1 - i))
+            .min()
+            .unwrap_or(0)
+    }
+}
 
+fn count_safe(reports : &[Report], rules: &Rules) -> u32 {
+    reports.iter().filter(|report| report.is_safe(rules)).count() as u32
 }
 
-fn parse(input: &str) -> Vec<Report> {
+fn parse(input: &str) -> Result<Vec<Report>, ParseError> {
     input.lines().map(|line| {
-        Report {
-            levels: line.split_whitespace().map(|x| x.parse().unwrap()).collect()
-        }
+        Ok(Report {
+            levels: line.split_whitespace()
+                .map(|x| parse::number(input, x))
+                .collect::<Result<_, _>>()?
+        })
     }).collect()
 }
 
 
-pub fn solve(input: &str) {
-    println!("{}", solve_1(parse(input)));
-    println!("{}", solve_2(parse(input)));
+const EXAMPLE: &str = "7 6 4 2 1
+1 2 7 8 9
+9 7 6 2 1
+1 3 2 4 5
+8 6 4 4 1
+1 3 6 7 9";
+
+pub struct Day02;
+
+impl Solution for Day02 {
+    type Input = Vec<Report>;
+
+    const EXAMPLES: &'static [Example] = &[Example {
+        input: EXAMPLE,
+        part1: Some("2"),
+        part2: Some("4"),
+    }];
+
+    fn parse(input: &str) -> Result<Vec<Report>, ParseError> {
+        parse(input)
+    }
+
+    fn part1(reports: &Vec<Report>) -> Option<Answer> {
+        Some(count_safe(reports, &Rules::STRICT).into())
+    }
+
+    fn part2(reports: &Vec<Report>) -> Option<Answer> {
+        Some(count_safe(reports, &Rules::DAMPENED).into())
+    }
 }
 
 
@@ -63,13 +162,6 @@ mod tests {
     use super::*;
 
 
-const EXAMPLE : &str = "7 6 4 2 1
-1 2 7 8 9
-9 7 6 2 1
-1 3 2 4 5
-8 6 4 4 1
-1 3 6 7 9";
-
     #[test]
     fn test_parse() {
         let expected = vec![
@@ -80,37 +172,72 @@ const EXAMPLE : &str = "7 6 4 2 1
             Report { levels: vec![8, 6, 4, 4, 1] },
             Report { levels: vec![1, 3, 6, 7, 9] },
         ];
-        assert_eq!(parse(EXAMPLE), expected);
+        assert_eq!(parse(EXAMPLE).unwrap(), expected);
     }
 
     #[test]
     fn test_is_safe() {
-        assert!(parse(EXAMPLE)[0].is_safe());
-        assert!(!parse(EXAMPLE)[1].is_safe());
-        assert!(!parse(EXAMPLE)[2].is_safe());
-        assert!(!parse(EXAMPLE)[3].is_safe());
-        assert!(!parse(EXAMPLE)[4].is_safe());
-        assert!(parse(EXAMPLE)[5].is_safe());
+        let reports = parse(EXAMPLE).unwrap();
+        assert!(reports[0].is_safe(&Rules::STRICT));
+        assert!(!reports[1].is_safe(&Rules::STRICT));
+        assert!(!reports[2].is_safe(&Rules::STRICT));
+        assert!(!reports[3].is_safe(&Rules::STRICT));
+        assert!(!reports[4].is_safe(&Rules::STRICT));
+        assert!(reports[5].is_safe(&Rules::STRICT));
     }
 
     #[test]
-    fn test_get_adj_difference() {
-        assert_eq!(parse(EXAMPLE)[0].get_adj_difference(), vec![1, 2, 2, 1]);
+    fn test_explain() {
+        let reports = parse(EXAMPLE).unwrap();
+        let explain = |index: usize| reports[index].explain(&Rules::STRICT);
+        assert_eq!(explain(0), None);
+        assert_eq!(explain(1), Some(Unsafe { index: 2, problem: Problem::Step { delta: 5 } }));
+        assert_eq!(explain(3), Some(Unsafe { index: 2, problem: Problem::Direction }));
+        assert_eq!(explain(4), Some(Unsafe { index: 3, problem: Problem::Step { delta: 0 } }));
+        assert_eq!(explain(1).unwrap().to_string(), "level 2 changes by 5");
+        assert_eq!(reports[3].explain(&Rules::DAMPENED), None);
     }
 
     #[test]
     fn test_solve_1() {
-        assert_eq!(solve_1(parse(EXAMPLE)), 2);
+        assert_eq!(count_safe(&parse(EXAMPLE).unwrap(), &Rules::STRICT), 2);
     }
 
     #[test]
     fn test_solve_2() {
-        assert!(parse(EXAMPLE)[0].is_safe_more_tolerant());
-        assert!(!parse(EXAMPLE)[1].is_safe_more_tolerant());
-        assert!(!parse(EXAMPLE)[2].is_safe_more_tolerant());
-        assert!(parse(EXAMPLE)[3].is_safe_more_tolerant());
-        assert!(parse(EXAMPLE)[4].is_safe_more_tolerant());
-        assert!(parse(EXAMPLE)[5].is_safe_more_tolerant());
+        let reports = parse(EXAMPLE).unwrap();
+        assert!(reports[0].is_safe(&Rules::DAMPENED));
+        assert!(!reports[1].is_safe(&Rules::DAMPENED));
+        assert!(!reports[2].is_safe(&Rules::DAMPENED));
+        assert!(reports[3].is_safe(&Rules::DAMPENED));
+        assert!(reports[4].is_safe(&Rules::DAMPENED));
+        assert!(reports[5].is_safe(&Rules::DAMPENED));
+        // removing the first or the last level
+        assert!(Report { levels: vec![9, 1, 2, 3] }.is_safe(&Rules::DAMPENED));
+        assert!(Report { levels: vec![1, 2, 3, 9] }.is_safe(&Rules::DAMPENED));
+    }
+
+    // compares with removing every combination of levels
+    #[test]
+    fn test_tolerated() {
+        fn brute_force(levels: &[u32], rules: &Rules) -> bool {
+            (0..=rules.tolerated.min(levels.len())).any(|removed| {
+                (0..levels.len()).combinations(removed).any(|indices| {
+                    let levels: Vec<_> = levels.iter().enumerate()
+                        .filter(|(i, _)| !indices.contains(i))
+                        .map(|(_, &level)| level)
+                        .collect();
+                    [true, false].into_iter().any(|increasing| {
+                        levels.iter().tuple_windows().all(|(&from, &to)| rules.allows(from, to, increasing))
+                    })
+                })
+            })
+        }
+
+        let rules = Rules { delta: 0..=2, tolerated: 2 };
+        for levels in (0..6).map(|_| 0..4u32).multi_cartesian_product() {
+            assert_eq!(Report { levels: levels.clone() }.is_safe(&rules), brute_force(&levels, &rules), "{levels:?}");
+        }
     }
 
 }
@@ -1,45 +1,134 @@
 
-use regex::Regex;
+use crate::parse::ParseError;
+use crate::solution::{Answer, Example, Solution};
 
-#[derive(Debug, PartialEq, Eq)]
-struct Operation {
-    left: usize,
-    right: usize
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Instruction {
+    Mul(u64, u64),
+    Do,
+    Dont,
 }
 
+impl Instruction {
+    /// The name and number of arguments of each instruction, a new one is
+    /// added here and in `new`.
+    const SIGNATURES: [(&'static str, usize); 3] = [("mul", 2), ("do", 0), ("don't", 0)];
 
-fn apply_operation(operations : &[Operation]) -> usize {
-    operations.iter().map(|op| op.left * op.right).sum()
+    fn new(name: &str, args: &[u64]) -> Instruction {
+        match (name, args) {
+            ("mul", &[left, right]) => Instruction::Mul(left, right),
+            ("do", []) => Instruction::Do,
+            ("don't", []) => Instruction::Dont,
+            _ => unreachable!("no instruction `{name}` with {} arguments", args.len()),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Token {
+    /// The byte offset of the instruction's name in the memory.
+    pub offset: usize,
+    pub instruction: Instruction,
 }
 
-fn get_operations (input: &str) -> Vec<Operation> {
-    let re = Regex::new(r"mul\((\d+),(\d+)\)").unwrap();
-    re.captures_iter(input)
-        .map(|cap| {
-            Operation {
-                left: cap[1].parse().unwrap(),
-                right: cap[2].parse().unwrap(),
+/// Finds all well-formed instructions in a single pass, everything else in
+/// the memory is corrupted and skipped.
+fn scan(memory: &str) -> Vec<Token> {
+    let bytes = memory.as_bytes();
+    let mut tokens = Vec::new();
+    let mut offset = 0;
+
+    while offset < bytes.len() {
+        let found = Instruction::SIGNATURES.iter().find_map(|&(name, arity)| {
+            let rest = bytes[offset..].strip_prefix(name.as_bytes())?;
+            let (args, length) = scan_args(rest, arity)?;
+            Some((Instruction::new(name, &args), name.len() + length))
+        });
+
+        match found {
+            Some((instruction, length)) => {
+                tokens.push(Token { offset, instruction });
+                offset += length;
             }
-        })
-        .collect()
+            None => offset += 1,
+        }
+    }
+
+    tokens
 }
 
-fn clean_input (input: &str) -> String {
-    let mut cleaned_input = input.to_string();
-    cleaned_input.push_str("do()");
+/// Reads `(a,b,...)` with `arity` numbers of one to three digits, returns
+/// them and the length of the text read.
+fn scan_args(bytes: &[u8], arity: usize) -> Option<(Vec<u64>, usize)> {
+    let mut args = Vec::with_capacity(arity);
+    let mut position = 1;
+    if bytes.first() != Some(&b'(') {
+        return None;
+    }
+
+    for index in 0..arity {
+        if index > 0 {
+            if bytes.get(position) != Some(&b',') {
+                return None;
+            }
+            position += 1;
+        }
+        let digits = bytes[position..].iter().take(3).take_while(|b| b.is_ascii_digit()).count();
+        if digits == 0 {
+            return None;
+        }
+        args.push(bytes[position..position + digits].iter().fold(0, |number, b| number * 10 + u64::from(b - b'0')));
+        position += digits;
+    }
+
+    if bytes.get(position) != Some(&b')') {
+        return None;
+    }
+    Some((args, position + 1))
+}
 
-    let re = Regex::new(r"(?s)don't\(\).*?do\(\)").unwrap();
+/// Sums the products of all enabled `mul`s, with `conditionals` the `do`s
+/// and `don't`s switch them on and off.
+fn evaluate(tokens: &[Token], conditionals: bool) -> u64 {
+    let mut enabled = true;
+    let mut sum = 0;
 
-    while let Some(mat) = re.find(&cleaned_input) {
-        cleaned_input.replace_range(mat.range(), "");
+    for token in tokens {
+        match token.instruction {
+            Instruction::Mul(left, right) if enabled => sum += left * right,
+            Instruction::Mul(..) => {}
+            Instruction::Do => enabled = true,
+            Instruction::Dont => enabled = !conditionals,
+        }
     }
 
-    cleaned_input
+    sum
 }
 
-pub fn solve(input: &str) {
-    println!("{}", apply_operation(&get_operations(input)));
-    println!("{}", apply_operation(&get_operations(&clean_input(input))));
+const EXAMPLE2: &str = "xmul(2,4)&mul[3,7]!^don't()_mul(5,5)+mul(32,64](mul(11,8)undo()?mul(8,5))";
+
+pub struct Day03;
+
+impl Solution for Day03 {
+    type Input = Vec<Token>;
+
+    const EXAMPLES: &'static [Example] = &[Example {
+        input: EXAMPLE2,
+        part1: Some("161"),
+        part2: Some("48"),
+    }];
+
+    fn parse(input: &str) -> Result<Vec<Token>, ParseError> {
+        Ok(scan(input))
+    }
+
+    fn part1(tokens: &Vec<Token>) -> Option<Answer> {
+        Some(evaluate(tokens, false).into())
+    }
+
+    fn part2(tokens: &Vec<Token>) -> Option<Answer> {
+        Some(evaluate(tokens, true).into())
+    }
 }
 
 #[cfg(test)]
@@ -47,22 +136,32 @@ mod tests {
     use super::*;
 
 const EXAMPLE: &str = "mul(832,177)";
-const EXAMPLE2: &str = "xmul(2,4)&mul[3,7]!^don't()_mul(5,5)+mul(32,64](mul(11,8)undo()?mul(8,5))";
-
     #[test]
     fn parse_example() {
-        assert_eq!(get_operations(EXAMPLE), vec![Operation{left : 832, right: 177}]);
+        assert_eq!(scan(EXAMPLE), vec![Token { offset: 0, instruction: Instruction::Mul(832, 177) }]);
     }
-    
+
     #[test]
-    fn test_apply_operation() {
-        assert_eq!(apply_operation(&[Operation{left : 832, right: 177}]), 832*177);
+    fn test_scan() {
+        let offsets = |memory| scan(memory).iter().map(|token| (token.offset, token.instruction)).collect::<Vec<_>>();
+        assert_eq!(offsets(EXAMPLE2), vec![
+            (1, Instruction::Mul(2, 4)),
+            (20, Instruction::Dont),
+            (28, Instruction::Mul(5, 5)),
+            (48, Instruction::Mul(11, 8)),
+            (59, Instruction::Do),
+            (64, Instruction::Mul(8, 5)),
+        ]);
+        assert_eq!(offsets("mul(1234,5)mul(1,2 mul( 1,2)do(1)don't"), vec![]);
+        assert_eq!(offsets("mulmul(3,4)"), vec![(3, Instruction::Mul(3, 4))]);
     }
 
     #[test]
-    fn test_clean() {
-        assert_eq!(apply_operation(&get_operations(&clean_input("do()mul(832,177)"))), 832*177);
-        assert_eq!(apply_operation(&get_operations(&clean_input("don't()mul(10,17)"))), 0);
-        assert_eq!(apply_operation(&get_operations(&clean_input(EXAMPLE2))), 48);
+    fn test_evaluate() {
+        assert_eq!(evaluate(&scan("do()mul(832,177)"), true), 832*177);
+        assert_eq!(evaluate(&scan("don't()mul(10,17)"), true), 0);
+        assert_eq!(evaluate(&scan("don't()mul(10,17)"), false), 170);
+        assert_eq!(evaluate(&scan(EXAMPLE2), false), 161);
+        assert_eq!(evaluate(&scan(EXAMPLE2), true), 48);
     }
 }
@@ -1,142 +1,69 @@
-struct Grid {
-    width: usize,
-    height: usize,
-    data: Vec<char>,
-}
+use crate::grid::{Coord, Grid};
+use crate::parse::ParseError;
+use crate::solution::{Answer, Example, Solution};
 
-impl Grid {
-    fn new(input: &str) -> Self {
-        let lines: Vec<&str> = input.lines().collect();
-        let height = lines.len();
-        let width = lines[0].len();
-        let data = lines.iter().flat_map(|line| line.chars()).collect();
-
-        Grid {
-            width,
-            height,
-            data,
-        }
-    }
+/// A word read from `start` in `direction`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Match {
+    pub start: Coord,
+    pub direction: Coord,
+}
 
-    fn get(&self, row: Option<usize>, col: Option<usize>) -> Option<char> {
-        if row? < self.height && col? < self.width {
-            Some(self.data[row? * self.width + col?])
-        } else {
-            None
-        }
-    }
+/// Two words on the diagonals through `center`, crossing in their middle
+/// letter.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Cross {
+    pub center: Coord,
+    /// The direction each word reads in, the first one on the diagonal from
+    /// the top left.
+    pub directions: [Coord; 2],
 }
 
-fn count_xmas(input: &str) -> usize {
-    let grid = Grid::new(input);
-
-    grid.data
-        .iter()
-        .enumerate()
-        .filter(|(_, &c)| c == 'X')
-        .flat_map(|(index, _)| {
-            let row = index / grid.width;
-            let col = index % grid.width;
-
-            [
-                [
-                    grid.get(Some(row), col.checked_add(1)),
-                    grid.get(Some(row), col.checked_add(2)),
-                    grid.get(Some(row), col.checked_add(3)),
-                ],
-                [
-                    grid.get(row.checked_add(1), Some(col)),
-                    grid.get(row.checked_add(2), Some(col)),
-                    grid.get(row.checked_add(3), Some(col)),
-                ],
-                [
-                    grid.get(row.checked_add(1), col.checked_add(1)),
-                    grid.get(row.checked_add(2), col.checked_add(2)),
-                    grid.get(row.checked_add(3), col.checked_add(3)),
-                ],
-                [
-                    grid.get(row.checked_sub(1), col.checked_sub(1)),
-                    grid.get(row.checked_sub(2), col.checked_sub(2)),
-                    grid.get(row.checked_sub(3), col.checked_sub(3)),
-                ],
-                [
-                    grid.get(Some(row), col.checked_sub(1)),
-                    grid.get(Some(row), col.checked_sub(2)),
-                    grid.get(Some(row), col.checked_sub(3)),
-                ],
-                [
-                    grid.get(row.checked_sub(1), Some(col)),
-                    grid.get(row.checked_sub(2), Some(col)),
-                    grid.get(row.checked_sub(3), Some(col)),
-                ],
-                [
-                    grid.get(row.checked_sub(1), col.checked_add(1)),
-                    grid.get(row.checked_sub(2), col.checked_add(2)),
-                    grid.get(row.checked_sub(3), col.checked_add(3)),
-                ],
-                [
-                    grid.get(row.checked_add(1), col.checked_sub(1)),
-                    grid.get(row.checked_add(2), col.checked_sub(2)),
-                    grid.get(row.checked_add(3), col.checked_sub(3)),
-                ],
-            ]
-        })
-        .filter(|ray| ray == &[Some('M'), Some('A'), Some('S')])
-        .count()
+fn reads(grid: &Grid<char>, word: &str, start: Coord, direction: Coord) -> bool {
+    (0..)
+        .zip(word.chars())
+        .all(|(step, letter)| grid.get(start + direction * step) == Some(&letter))
 }
 
-fn count_mas(input: &str) -> usize {
-    let grid = Grid::new(input);
-
-    grid.data
-        .iter()
-        .enumerate()
-        .filter(|(_, &c)| c == 'A')
-        .flat_map(|(index, _)| {
-            let row = index / grid.width;
-            let col = index % grid.width;
-
-            [
-                [
-                    grid.get(row.checked_sub(1), col.checked_sub(1)),
-                    grid.get(row.checked_add(1), col.checked_add(1)),
-                    grid.get(row.checked_sub(1), col.checked_add(1)),
-                    grid.get(row.checked_add(1), col.checked_sub(1)),
-                ],
-                [
-                    grid.get(row.checked_sub(1), col.checked_sub(1)),
-                    grid.get(row.checked_add(1), col.checked_add(1)),
-                    grid.get(row.checked_add(1), col.checked_sub(1)),
-                    grid.get(row.checked_sub(1), col.checked_add(1)),
-                ],
-                [
-                    grid.get(row.checked_add(1), col.checked_add(1)),
-                    grid.get(row.checked_sub(1), col.checked_sub(1)),
-                    grid.get(row.checked_add(1), col.checked_sub(1)),
-                    grid.get(row.checked_sub(1), col.checked_add(1)),
-                ],
-                [
-                    grid.get(row.checked_add(1), col.checked_add(1)),
-                    grid.get(row.checked_sub(1), col.checked_sub(1)),
-                    grid.get(row.checked_sub(1), col.checked_add(1)),
-                    grid.get(row.checked_add(1), col.checked_sub(1)),
-                ],
-            ]
+/// Finds `word` in all 8 directions, a word reading the same in several
+/// directions from one start is found once per direction.
+pub fn find_words<'a>(grid: &'a Grid<char>, word: &'a str) -> impl Iterator<Item = Match> + 'a {
+    let first = word.chars().next();
+
+    grid.iter()
+        .filter(move |&(_, &letter)| Some(letter) == first)
+        .flat_map(move |(start, _)| {
+            Coord::ALL_DIRECTIONS
+                .into_iter()
+                .filter(move |&direction| reads(grid, word, start, direction))
+                .map(move |direction| Match { start, direction })
         })
-        .filter(|x| x == &[Some('M'), Some('S'), Some('M'), Some('S')])
-        .count()
 }
 
-pub fn solve(input: &str) {
-    println!("{}", count_xmas(input));
-    println!("{}", count_mas(input));
-}
+/// Finds `word` crossed with itself as an `X`; only words of odd length have
+/// a middle letter to cross in.
+pub fn find_crosses<'a>(grid: &'a Grid<char>, word: &'a str) -> impl Iterator<Item = Cross> + 'a {
+    let length = word.chars().count();
+    let middle = word.chars().nth(length / 2).filter(|_| length % 2 == 1);
+    let half = (length / 2) as i64;
 
-#[cfg(test)]
-mod tests {
-    use super::*;
+    grid.iter()
+        .filter(move |&(_, &letter)| Some(letter) == middle)
+        .filter_map(move |(center, _)| {
+            let on_diagonal = |diagonal: Coord| {
+                [diagonal, -diagonal]
+                    .into_iter()
+                    .find(|&direction| reads(grid, word, center - direction * half, direction))
+            };
+            let directions = [
+                on_diagonal(Coord::DOWN_RIGHT)?,
+                on_diagonal(Coord::DOWN_LEFT)?,
+            ];
+            Some(Cross { center, directions })
+        })
+}
 
-    const EXAMPLE: &str = "MMMSXXMASM
+const EXAMPLE: &str = "MMMSXXMASM
 MSAMXMSMSA
 AMXSXMAAMM
 MSAMASMSMX
@@ -147,15 +74,77 @@ SAXAMASAAA
 MAMMMXMMMM
 MXMXAXMASX";
 
+pub struct Day04;
+
+impl Solution for Day04 {
+    type Input = Grid<char>;
+
+    const EXAMPLES: &'static [Example] = &[Example {
+        input: EXAMPLE,
+        part1: Some("18"),
+        part2: Some("9"),
+    }];
+
+    fn parse(input: &str) -> Result<Grid<char>, ParseError> {
+        Grid::parse(input)
+    }
+
+    fn part1(grid: &Grid<char>) -> Option<Answer> {
+        Some(find_words(grid, "XMAS").count().into())
+    }
+
+    fn part2(grid: &Grid<char>) -> Option<Answer> {
+        Some(find_crosses(grid, "MAS").count().into())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
     #[test]
     fn test_parse() {
-        let grid = Grid::new(EXAMPLE);
-        assert_eq!(grid.get(Some(0), Some(0)), Some('M'));
-        assert_eq!(grid.get(Some(9), Some(9)), Some('X'));
+        let grid = Grid::parse(EXAMPLE).unwrap();
+        assert_eq!(grid.get(Coord::new(0, 0)), Some(&'M'));
+        assert_eq!(grid.get(Coord::new(9, 9)), Some(&'X'));
     }
 
     #[test]
-    fn test_count_xmas() {
-        assert_eq!(count_xmas(EXAMPLE), 18);
+    fn test_find_words() {
+        let grid = Grid::parse(EXAMPLE).unwrap();
+        assert_eq!(find_words(&grid, "XMAS").count(), 18);
+        assert_eq!(
+            find_words(&grid, "XMAS").next(),
+            Some(Match {
+                start: Coord::new(0, 4),
+                direction: Coord::DOWN_RIGHT
+            })
+        );
+
+        let grid = Grid::parse("ABA\nBAB").unwrap();
+        let matches: Vec<_> = find_words(&grid, "AB").collect();
+        assert_eq!(matches.len(), 7);
+        assert!(matches.contains(&Match {
+            start: Coord::new(1, 1),
+            direction: Coord::LEFT
+        }));
+        assert_eq!(find_words(&grid, "").count(), 0);
+    }
+
+    #[test]
+    fn test_find_crosses() {
+        let grid = Grid::parse(EXAMPLE).unwrap();
+        assert_eq!(find_crosses(&grid, "MAS").count(), 9);
+
+        let grid = Grid::parse("M.S\n.A.\nM.S").unwrap();
+        assert_eq!(
+            find_crosses(&grid, "MAS").collect::<Vec<_>>(),
+            [Cross {
+                center: Coord::new(1, 1),
+                directions: [Coord::DOWN_RIGHT, Coord::UP_RIGHT]
+            }]
+        );
+        assert_eq!(find_crosses(&grid, "SAM").count(), 1);
+        assert_eq!(find_crosses(&grid, "MA").count(), 0);
     }
 }
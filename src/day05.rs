@@ -1,78 +1,206 @@
-use std::{cmp::Ordering, collections::HashSet};
+use std::collections::{HashMap, HashSet};
+use std::fmt;
 
-fn parse(input: &str) -> (HashSet<(u32, u32)>, Vec<Vec<u32>>) {
-    let mut parts = input.split("\n\n");
-    let first_part = parts.next().unwrap();
-    let second_part = parts.next().unwrap();
+use crate::parse::{self, ParseError};
+use crate::solution::{Answer, Example, Solution};
 
-    let set = first_part
-        .lines()
-        .map(|line| {
-            let mut parts = line.split('|');
-            let first_number: u32 = parts.next().unwrap().parse().unwrap();
-            let second_number: u32 = parts.next().unwrap().parse().unwrap();
-            (first_number, second_number)
-        })
-        .collect();
+/// The rules as a directed graph, from each page to the pages that have to
+/// come after it.
+#[derive(Debug, Default, PartialEq, Eq)]
+pub struct Rules {
+    after: HashMap<u32, HashSet<u32>>,
+}
 
-    let vec = second_part
-        .lines()
-        .map(|line| line.split(',').map(|num| num.parse().unwrap()).collect())
-        .collect();
+impl Rules {
+    fn add(&mut self, before: u32, after: u32) {
+        self.after.entry(before).or_default().insert(after);
+    }
+
+    fn successors(&self, page: u32) -> impl Iterator<Item = u32> + '_ {
+        self.after.get(&page).into_iter().flatten().copied()
+    }
+
+    fn must_come_before(&self, page: u32, other: u32) -> bool {
+        self.after
+            .get(&page)
+            .is_some_and(|after| after.contains(&other))
+    }
+
+    /// Finds a cycle among `pages`, using only the rules between them.
+    pub fn find_cycle(&self, pages: &HashSet<u32>) -> Option<Vec<u32>> {
+        fn visit(
+            rules: &Rules,
+            pages: &HashSet<u32>,
+            page: u32,
+            finished: &mut HashSet<u32>,
+            path: &mut Vec<u32>,
+        ) -> Option<Vec<u32>> {
+            if let Some(start) = path.iter().position(|&on_path| on_path == page) {
+                return Some(path[start..].to_vec());
+            }
+            if finished.contains(&page) {
+                return None;
+            }
+
+            path.push(page);
+            for next in rules.successors(page).filter(|next| pages.contains(next)) {
+                if let Some(cycle) = visit(rules, pages, next, finished, path) {
+                    return Some(cycle);
+                }
+            }
+            path.pop();
+            finished.insert(page);
+
+            None
+        }
+
+        let mut finished = HashSet::new();
+        pages
+            .iter()
+            .find_map(|&page| visit(self, pages, page, &mut finished, &mut Vec::new()))
+    }
+
+    /// Sorts the pages of an update topologically, using only the rules
+    /// between them. The rules have to decide the place of every page.
+    pub fn sort(&self, pages: &[u32]) -> Result<Vec<u32>, OrderError> {
+        let mut remaining = HashSet::new();
+        for &page in pages {
+            if !remaining.insert(page) {
+                return Err(OrderError::Repeated(page));
+            }
+        }
+        let mut predecessors: HashMap<u32, usize> =
+            remaining.iter().map(|&page| (page, 0)).collect();
+        for &page in &remaining {
+            for next in self
+                .successors(page)
+                .filter(|next| remaining.contains(next))
+            {
+                *predecessors.get_mut(&next).unwrap() += 1;
+            }
+        }
+
+        let mut sorted = Vec::with_capacity(remaining.len());
+        while !remaining.is_empty() {
+            let mut first = pages
+                .iter()
+                .copied()
+                .filter(|page| remaining.contains(page) && predecessors[page] == 0);
+            let page = match (first.next(), first.next()) {
+                (Some(page), None) => page,
+                (Some(page), Some(other)) => return Err(OrderError::Ambiguous(page, other)),
+                (None, _) => return Err(OrderError::Cycle(self.find_cycle(&remaining).unwrap())),
+            };
 
-    (set, vec)
+            remaining.remove(&page);
+            for next in self
+                .successors(page)
+                .filter(|next| remaining.contains(next))
+            {
+                *predecessors.get_mut(&next).unwrap() -= 1;
+            }
+            sorted.push(page);
+        }
+
+        Ok(sorted)
+    }
 }
 
-fn check_if_sorted(pages: &[u32], order: &HashSet<(u32, u32)>) -> bool {
-    pages.is_sorted_by(|a, b| {
-        if a == b {
-            true
-        } else { order.contains(&(*a, *b)) }
-    })
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum OrderError {
+    /// The rules do not decide which of the two pages comes first.
+    Ambiguous(u32, u32),
+    /// The rules contradict each other, each page has to come before the
+    /// next one and the last before the first.
+    Cycle(Vec<u32>),
+    /// The page appears more than once, so it has no single place.
+    Repeated(u32),
 }
 
-fn solve_1(pages: &[Vec<u32>], order: &HashSet<(u32, u32)>) -> u32 {
-    pages
-        .iter()
-        .filter(|x| check_if_sorted(x, order))
-        .map(|x| x[x.len() / 2])
-        .sum()
+impl fmt::Display for OrderError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            OrderError::Ambiguous(first, second) => {
+                write!(f, "no rule orders pages {first} and {second}")
+            }
+            OrderError::Cycle(pages) => {
+                let pages: Vec<_> = pages.iter().map(u32::to_string).collect();
+                write!(f, "the rules order pages {} in a cycle", pages.join(", "))
+            }
+            OrderError::Repeated(page) => write!(f, "page {page} appears more than once"),
+        }
+    }
 }
 
-fn solve_2(pages: &[Vec<u32>], order: &HashSet<(u32, u32)>) -> u32 {
-    pages
-        .iter()
-        .filter(|x| !check_if_sorted(x, order))
-        .map(|x| {
-            let mut x_clone = x.clone();
-            x_clone.sort_by(|a, b| {
-                if a == b {
-                    Ordering::Equal
-                } else if order.contains(&(*a, *b)) {
-                    Ordering::Less
-                } else {
-                    Ordering::Greater
-                }
-            });
-            x_clone
+#[derive(Debug, PartialEq, Eq)]
+pub struct Update {
+    pages: Vec<u32>,
+}
+
+impl Update {
+    /// Whether no rule puts a page before one that precedes it.
+    fn is_sorted(&self, rules: &Rules) -> bool {
+        self.pages.iter().enumerate().all(|(index, &page)| {
+            self.pages[index + 1..]
+                .iter()
+                .all(|&later| !rules.must_come_before(later, page))
         })
-        .map(|x| x[x.len() / 2])
-        .sum()
+    }
+}
+
+fn parse(input: &str) -> Result<(Rules, Vec<Update>), ParseError> {
+    let (first_part, second_part) = parse::split_once(input, input, "\n\n")?;
+
+    let mut rules = Rules::default();
+    for line in first_part.lines() {
+        let (first_number, second_number) = parse::split_once(input, line, "|")?;
+        rules.add(
+            parse::number(input, first_number)?,
+            parse::number(input, second_number)?,
+        );
+    }
+
+    let updates = second_part
+        .lines()
+        .map(|line| {
+            let pages = line
+                .split(',')
+                .map(|num| parse::number(input, num))
+                .collect::<Result<Vec<_>, _>>()?;
+            Ok(Update { pages })
+        })
+        .collect::<Result<_, _>>()?;
+
+    Ok((rules, updates))
 }
 
-pub fn solve(input: &str) {
-    let order = parse(input).0;
-    let pages = parse(input).1;
+fn middle(pages: &[u32]) -> u32 {
+    pages[pages.len() / 2]
+}
 
-    println!("{}", solve_1(&pages, &order));
-    println!("{}", solve_2(&pages, &order));
+fn solve_1(rules: &Rules, updates: &[Update]) -> u32 {
+    updates
+        .iter()
+        .filter(|update| update.is_sorted(rules))
+        .map(|update| middle(&update.pages))
+        .sum()
 }
 
-#[cfg(test)]
-mod tests {
-    use super::*;
+fn solve_2(rules: &Rules, updates: &[Update]) -> Result<u32, String> {
+    let mut total = 0;
+    for (index, update) in updates.iter().enumerate() {
+        if !update.is_sorted(rules) {
+            let sorted = rules
+                .sort(&update.pages)
+                .map_err(|err| format!("update {}: {err}", index + 1))?;
+            total += middle(&sorted);
+        }
+    }
+
+    Ok(total)
+}
 
-    const EXAMPLE: &str = "47|53
+const EXAMPLE: &str = "47|53
 97|13
 97|61
 97|47
@@ -101,55 +229,101 @@ mod tests {
 61,13,29
 97,13,75,29,47";
 
-    fn example_locations() -> (HashSet<(u32, u32)>, Vec<Vec<u32>>) {
-        let mut set = HashSet::new();
-        set.insert((47, 53));
-        set.insert((97, 13));
-        set.insert((97, 61));
-        set.insert((97, 47));
-        set.insert((75, 29));
-        set.insert((61, 13));
-        set.insert((75, 53));
-        set.insert((29, 13));
-        set.insert((97, 29));
-        set.insert((53, 29));
-        set.insert((61, 53));
-        set.insert((97, 53));
-        set.insert((61, 29));
-        set.insert((47, 13));
-        set.insert((75, 47));
-        set.insert((97, 75));
-        set.insert((47, 61));
-        set.insert((75, 61));
-        set.insert((47, 29));
-        set.insert((75, 13));
-        set.insert((53, 13));
-
-        let vec = vec![
-            vec![75, 47, 61, 53, 29],
-            vec![97, 61, 53, 29, 13],
-            vec![75, 29, 13],
-            vec![75, 97, 47, 61, 53],
-            vec![61, 13, 29],
-            vec![97, 13, 75, 29, 47],
-        ];
-
-        (set, vec)
+pub struct Day05;
+
+impl Solution for Day05 {
+    type Input = (Rules, Vec<Update>);
+
+    const EXAMPLES: &'static [Example] = &[Example {
+        input: EXAMPLE,
+        part1: Some("143"),
+        part2: Some("123"),
+    }];
+
+    fn parse(input: &str) -> Result<Self::Input, ParseError> {
+        parse(input)
+    }
+
+    fn part1((rules, updates): &Self::Input) -> Option<Answer> {
+        Some(solve_1(rules, updates).into())
     }
 
+    fn try_part2((rules, updates): &Self::Input) -> Result<Option<Answer>, String> {
+        Ok(Some(solve_2(rules, updates)?.into()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
     #[test]
     fn parse_example() {
-        assert_eq!(parse(EXAMPLE), example_locations());
+        let (rules, updates) = parse(EXAMPLE).unwrap();
+        assert_eq!(rules.after.values().map(HashSet::len).sum::<usize>(), 21);
+        assert!(rules.after[&97].contains(&13));
+        assert_eq!(updates[3].pages, [75, 97, 47, 61, 53]);
+    }
+
+    #[test]
+    fn parse_invalid() {
+        let err = parse("47|53\n97-13\n\n75,47").unwrap_err();
+        assert_eq!((err.line, err.column, err.text.as_str()), (2, 1, "97-13"));
+        let err = parse("47|53\n\n75,,47").unwrap_err();
+        assert_eq!((err.line, err.column), (3, 4));
+        assert!(parse("47|53").is_err());
+    }
+
+    #[test]
+    fn test_unorderable_updates() {
+        let input = parse("1|2\n2|3\n\n1,2,3\n3,2,4").unwrap();
+        assert_eq!(Day05::part1(&input), Some(2u32.into()));
+        assert_eq!(
+            Day05::try_part2(&input),
+            Err("update 2: no rule orders pages 2 and 4".to_string())
+        );
+
+        let input = parse("1|2\n\n2,1,2").unwrap();
+        assert_eq!(
+            Day05::try_part2(&input),
+            Err("update 1: page 2 appears more than once".to_string())
+        );
     }
 
     #[test]
     fn test_is_sorted() {
-        let (order, pages) = example_locations();
-        assert!(check_if_sorted(&pages[0], &order));
-        assert!(check_if_sorted(&pages[1], &order));
-        assert!(check_if_sorted(&pages[2], &order));
-        assert!(!check_if_sorted(&pages[3], &order));
-        assert!(!check_if_sorted(&pages[4], &order));
-        assert!(!check_if_sorted(&pages[5], &order));
+        let (rules, updates) = parse(EXAMPLE).unwrap();
+        let sorted: Vec<_> = updates
+            .iter()
+            .map(|update| update.is_sorted(&rules))
+            .collect();
+        assert_eq!(sorted, [true, true, true, false, false, false]);
+    }
+
+    #[test]
+    fn test_sort() {
+        let (rules, _) = parse(EXAMPLE).unwrap();
+        assert_eq!(rules.sort(&[61, 13, 29]), Ok(vec![61, 29, 13]));
+        assert_eq!(rules.sort(&[13, 75]), Ok(vec![75, 13]));
+        assert_eq!(rules.sort(&[]), Ok(vec![]));
+        assert_eq!(
+            rules.find_cycle(&rules.after.keys().copied().collect()),
+            None
+        );
+
+        let mut rules = Rules::default();
+        rules.add(1, 2);
+        rules.add(2, 3);
+        rules.add(3, 1);
+        assert_eq!(rules.sort(&[1, 2]), Ok(vec![1, 2]));
+        assert_eq!(rules.sort(&[1, 2, 1]), Err(OrderError::Repeated(1)));
+        let Err(OrderError::Cycle(cycle)) = rules.sort(&[4, 1, 2, 3]) else {
+            panic!("expected a cycle");
+        };
+        assert_eq!(cycle.len(), 3);
+        assert_eq!(
+            OrderError::Cycle(vec![1, 2, 3]).to_string(),
+            "the rules order pages 1, 2, 3 in a cycle"
+        );
     }
 }
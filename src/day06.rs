@@ -1,46 +1,14 @@
 use std::collections::HashSet;
 use std::iter::successors;
-use itertools::Itertools;
-
-#[derive(Clone)]
-struct Grid {
-    width: usize,
-    height: usize,
-    data: Vec<char>,
-}
-
-impl Grid {
-    fn new(input: &str) -> Self {
-        let lines: Vec<&str> = input.lines().collect();
-        let height = lines.len();
-        let width = lines[0].len();
-        let data = lines.iter().flat_map(|line| line.chars()).collect();
-
-        Grid {
-            width,
-            height,
-            data,
-        }
-    }
 
-    fn get(&self, row: Option<usize>, col: Option<usize>) -> Option<char> {
-        if row? < self.height && col? < self.width {
-            Some(self.data[row? * self.width + col?])
-        } else {
-            None
-        }
-    }
+use rayon::prelude::*;
 
-    fn find_starting_point(&self) -> (usize, usize) {
-        (0..self.height)
-            .cartesian_product(0..self.width)
-            .find(|&(row, col)| self.get(Some(row), Some(col)).unwrap() == '^')
-            .unwrap()
-    }
-}
+use crate::grid::{Coord, Grid};
+use crate::parse::{self, ParseError};
+use crate::solution::{Answer, Example, Solution};
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
-enum Direction {
+pub enum Direction {
     Up,
     Down,
     Left,
@@ -56,94 +24,228 @@ impl Direction {
             Direction::Right => Direction::Down,
         }
     }
+
+    fn offset(&self) -> Coord {
+        match self {
+            Direction::Up => Coord::UP,
+            Direction::Down => Coord::DOWN,
+            Direction::Left => Coord::LEFT,
+            Direction::Right => Coord::RIGHT,
+        }
+    }
 }
 
 #[derive(Debug, Clone, PartialEq, Eq, Hash)]
-struct State {
-    row: usize,
-    col: usize,
-    direction: Direction,
-}
-
-fn step(state: &State, grid: &Grid) -> Option<State> {
-    let next_pos = match state.direction {
-        Direction::Up => (state.row.checked_sub(1), Some(state.col)),
-        Direction::Down => (state.row.checked_add(1), Some(state.col)),
-        Direction::Left => (Some(state.row), state.col.checked_sub(1)),
-        Direction::Right => (Some(state.row), state.col.checked_add(1)),
-    };
+pub struct State {
+    pub position: Coord,
+    /// The direction the guard last moved in, or faces at the start.
+    pub direction: Direction,
+}
 
-    if let Some(c) = grid.get(next_pos.0, next_pos.1) {
-        if c == '#' {
-            let new_state = State {
-                row: state.row,
-                col: state.col,
-                direction: state.direction.rotated_right(),
-            };
-            step(&new_state, grid)
-        } else {
-            Some(State {
-                row: next_pos.0.unwrap(),
-                col: next_pos.1.unwrap(),
-                direction: state.direction,
-            })
+/// The guard's next state, `None` once she leaves the grid. A guard boxed in
+/// on all four sides stays where she is.
+fn step(state: &State, grid: &Grid<char>) -> Option<State> {
+    let mut direction = state.direction;
+    for _ in 0..4 {
+        let next_pos = state.position + direction.offset();
+        if *grid.get(next_pos)? != '#' {
+            return Some(State {
+                position: next_pos,
+                direction,
+            });
         }
-    } else {
-        None
+        direction = direction.rotated_right();
     }
+
+    Some(state.clone())
 }
 
-fn parse(input: &str) -> (State, Grid) {
-    let grid = Grid::new(input);
-    let (row, col) = grid.find_starting_point();
+fn parse(input: &str) -> Result<(State, Grid<char>), ParseError> {
+    parse::only_chars(input, input, ".#^")?;
+    parse::exactly_one(input, '^')?;
+
+    let grid = Grid::parse(input)?;
+    let position = grid.find(&'^').unwrap();
     let state = State {
-        row,
-        col,
+        position,
         direction: Direction::Up,
     };
 
-    (state, grid)
+    Ok((state, grid))
 }
 
-fn get_path(state: State, grid: &Grid) -> impl Iterator<Item = State> + use<'_> {
+fn get_path(state: State, grid: &Grid<char>) -> impl Iterator<Item = State> + use<'_> {
     successors(Some(state), move |state| step(state, grid))
 }
 
-fn count_distinct_positions(state: State, grid: &Grid) -> usize {
-    let unique_pos: HashSet<_> = get_path(state, grid)
-        .map(|state| (state.row, state.col))
+/// The guard's path until she leaves the grid or would repeat herself.
+pub fn walk(state: State, grid: &Grid<char>) -> Vec<State> {
+    let mut visited = HashSet::new();
+    get_path(state, grid)
+        .take_while(|state| visited.insert(state.clone()))
+        .collect()
+}
+
+/// The guard's path until she leaves the grid, `None` if she walks in a loop
+/// instead.
+fn path_out(state: State, grid: &Grid<char>) -> Option<Vec<State>> {
+    let path = walk(state, grid);
+    let last = path.last()?;
+
+    step(last, grid).is_none().then_some(path)
+}
+
+/// Draws the path like the puzzle text does: `|` and `-` where the guard
+/// walked vertically or horizontally, `+` where she did both or turned, and
+/// `O` at the obstruction.
+pub fn render(grid: &Grid<char>, path: &[State], obstruction: Option<Coord>) -> String {
+    let mut vertical = Grid::new(grid.width(), grid.height(), false);
+    let mut horizontal = vertical.clone();
+    for (index, state) in path.iter().enumerate() {
+        let leaving = path.get(index + 1).map(|next| next.direction);
+        for direction in [Some(state.direction), leaving].into_iter().flatten() {
+            match direction {
+                Direction::Up | Direction::Down => vertical[state.position] = true,
+                Direction::Left | Direction::Right => horizontal[state.position] = true,
+            }
+        }
+    }
+
+    let mut drawing = grid.clone();
+    for coord in grid.coords().filter(|&coord| grid[coord] == '.') {
+        drawing[coord] = match (vertical[coord], horizontal[coord]) {
+            (true, true) => '+',
+            (true, false) => '|',
+            (false, true) => '-',
+            (false, false) => '.',
+        };
+    }
+    if let Some(obstruction) = obstruction {
+        drawing[obstruction] = 'O';
+    }
+
+    drawing.to_string()
+}
+
+/// `None` if the guard never leaves the grid.
+fn count_distinct_positions(state: State, grid: &Grid<char>) -> Option<usize> {
+    let unique_pos: HashSet<_> = path_out(state, grid)?
+        .into_iter()
+        .map(|state| state.position)
         .collect();
-    unique_pos.len()
+    Some(unique_pos.len())
 }
 
-fn is_loop(state: State, grid: &Grid) -> bool {
-    let mut visited = HashSet::new();
-    get_path(state, grid).any(|state| !visited.insert(state))
+/// The obstacles of each row and column in order, so that the guard can jump
+/// from one to the next instead of walking.
+struct Obstacles {
+    in_rows: Vec<Vec<i64>>,
+    in_columns: Vec<Vec<i64>>,
 }
 
-fn count_different_loops(state: State, grid: &Grid) -> usize {
-    (0..grid.width * grid.height)
-        .map(|idx| {
-            let mut new_grid = grid.clone();
-            new_grid.data[idx] = '#';
-            new_grid
-        })
-        .filter(|grid| is_loop(state.clone(), grid))
-        .count()
+impl Obstacles {
+    fn new(grid: &Grid<char>) -> Self {
+        let mut obstacles = Obstacles {
+            in_rows: vec![Vec::new(); grid.height()],
+            in_columns: vec![Vec::new(); grid.width()],
+        };
+        for coord in grid.find_all(&'#') {
+            obstacles.in_rows[coord.row as usize].push(coord.col);
+            obstacles.in_columns[coord.col as usize].push(coord.row);
+        }
+
+        obstacles
+    }
+
+    /// Where the guard walking from `position` stops in front of the next
+    /// obstacle, including `extra`; `None` if she leaves the grid.
+    fn next_stop(&self, position: Coord, direction: Direction, extra: Coord) -> Option<Coord> {
+        let (line, along, extra) = match direction {
+            Direction::Up | Direction::Down => (
+                &self.in_columns[position.col as usize],
+                position.row,
+                (extra.col == position.col).then_some(extra.row),
+            ),
+            Direction::Left | Direction::Right => (
+                &self.in_rows[position.row as usize],
+                position.col,
+                (extra.row == position.row).then_some(extra.col),
+            ),
+        };
+
+        let index = line.partition_point(|&obstacle| obstacle < along);
+        let obstacle = match direction {
+            Direction::Up | Direction::Left => line[..index]
+                .last()
+                .copied()
+                .into_iter()
+                .chain(extra.filter(|&extra| extra < along))
+                .max(),
+            Direction::Down | Direction::Right => line[index..]
+                .first()
+                .copied()
+                .into_iter()
+                .chain(extra.filter(|&extra| extra > along))
+                .min(),
+        }?;
+
+        Some(position + direction.offset() * (obstacle.abs_diff(along) as i64 - 1))
+    }
 }
 
-pub fn solve(input: &str) {
-    let (state, grid) = parse(input);
+/// Whether the guard walks in a loop with an extra obstacle, jumping from
+/// obstacle to obstacle.
+fn is_loop(state: State, obstacles: &Obstacles, extra: Coord) -> bool {
+    let State {
+        mut position,
+        mut direction,
+    } = state;
+    let mut turns = HashSet::new();
+
+    while let Some(stop) = obstacles.next_stop(position, direction, extra) {
+        if !turns.insert((stop, direction)) {
+            return true;
+        }
+        position = stop;
+        direction = direction.rotated_right();
+    }
 
-    println!("{}", count_distinct_positions(state.clone(), &grid));
-    println!("{}", count_different_loops(state.clone(), &grid));
+    false
 }
 
-#[cfg(test)]
-mod tests {
-    use super::*;
+/// Where a single obstruction makes the guard walk in a loop, in the order
+/// she reaches them.
+///
+/// Only an obstruction on the guard's path changes it. The guard walks as
+/// before until she first reaches it, so each one is tried from just there.
+/// `None` if she walks in a loop without any obstruction.
+pub fn loop_obstructions(state: State, grid: &Grid<char>) -> Option<Vec<Coord>> {
+    let obstacles = Obstacles::new(grid);
+    let path = path_out(state, grid)?;
+
+    let mut tried = HashSet::from([path[0].position]);
+    let candidates: Vec<_> = path
+        .windows(2)
+        .filter(|states| tried.insert(states[1].position))
+        .map(|states| {
+            let before = State {
+                position: states[0].position,
+                direction: states[1].direction,
+            };
+            (before, states[1].position)
+        })
+        .collect();
+
+    let obstructions = candidates
+        .into_par_iter()
+        .filter(|&(ref before, obstruction)| is_loop(before.clone(), &obstacles, obstruction))
+        .map(|(_, obstruction)| obstruction)
+        .collect();
+
+    Some(obstructions)
+}
 
-    const EXAMPLE: &str = "....#.....
+const EXAMPLE: &str = "....#.....
 .........#
 ..........
 ..#.......
@@ -154,11 +256,128 @@ mod tests {
 #.........
 ......#...";
 
+pub struct Day06;
+
+impl Solution for Day06 {
+    type Input = (State, Grid<char>);
+
+    const EXAMPLES: &'static [Example] = &[Example {
+        input: EXAMPLE,
+        part1: Some("41"),
+        part2: Some("6"),
+    }];
+
+    fn parse(input: &str) -> Result<Self::Input, ParseError> {
+        parse(input)
+    }
+
+    fn try_part1((state, grid): &Self::Input) -> Result<Option<Answer>, String> {
+        match count_distinct_positions(state.clone(), grid) {
+            Some(count) => Ok(Some(count.into())),
+            None => Err("the guard never leaves the grid".to_string()),
+        }
+    }
+
+    fn try_part2((state, grid): &Self::Input) -> Result<Option<Answer>, String> {
+        match loop_obstructions(state.clone(), grid) {
+            Some(obstructions) => Ok(Some(obstructions.len().into())),
+            None => Err("the guard never leaves the grid".to_string()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
     #[test]
     fn test_example() {
-        let (state, grid) = parse(EXAMPLE);
-        assert_eq!(count_distinct_positions(state.clone(), &grid), 41);
-        assert_eq!(count_different_loops(state.clone(), &grid), 6);
+        let (state, grid) = parse(EXAMPLE).unwrap();
+        assert_eq!(count_distinct_positions(state.clone(), &grid), Some(41));
+        assert_eq!(
+            loop_obstructions(state.clone(), &grid).unwrap(),
+            [
+                Coord::new(6, 3),
+                Coord::new(7, 6),
+                Coord::new(8, 3),
+                Coord::new(8, 1),
+                Coord::new(7, 7),
+                Coord::new(9, 7),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_render() {
+        let (state, mut grid) = parse(EXAMPLE).unwrap();
+        let obstruction = Coord::new(6, 3);
+        grid.set(obstruction, '#');
+        let path = walk(state, &grid);
+        assert_eq!(path.len(), 19);
+        assert_eq!(
+            render(&grid, &path, Some(obstruction)),
+            "\
+....#.....
+....+---+#
+....|...|.
+..#.|...|.
+....|..#|.
+....|...|.
+.#.O^---+.
+........#.
+#.........
+......#..."
+        );
     }
 
+    #[test]
+    fn test_next_stop() {
+        let (state, grid) = parse(EXAMPLE).unwrap();
+        let obstacles = Obstacles::new(&grid);
+        let nowhere = Coord::new(-1, -1);
+        assert_eq!(
+            obstacles.next_stop(state.position, Direction::Up, nowhere),
+            Some(Coord::new(1, 4))
+        );
+        assert_eq!(
+            obstacles.next_stop(Coord::new(1, 4), Direction::Right, nowhere),
+            Some(Coord::new(1, 8))
+        );
+        assert_eq!(
+            obstacles.next_stop(Coord::new(1, 4), Direction::Right, Coord::new(1, 6)),
+            Some(Coord::new(1, 5))
+        );
+        assert_eq!(
+            obstacles.next_stop(Coord::new(1, 4), Direction::Left, nowhere),
+            None
+        );
+        assert!(is_loop(state.clone(), &obstacles, Coord::new(6, 3)));
+        assert!(!is_loop(state, &obstacles, nowhere));
+    }
+
+    #[test]
+    fn parse_invalid() {
+        let err = parse("..#\n.^x").unwrap_err();
+        assert_eq!((err.line, err.column, err.text.as_str()), (2, 3, "x"));
+        assert!(parse("..#\n...").is_err());
+        let err = parse("..^\n.^.").unwrap_err();
+        assert_eq!((err.line, err.column), (2, 2));
+    }
+
+    #[test]
+    fn test_never_leaving() {
+        let input = parse(".#.\n#^#\n.#.").unwrap();
+        assert_eq!(walk(input.0.clone(), &input.1).len(), 1);
+        assert_eq!(
+            Day06::try_part1(&input),
+            Err("the guard never leaves the grid".to_string())
+        );
+
+        let input = parse(".#..\n...#\n#^..\n..#.").unwrap();
+        assert_eq!(count_distinct_positions(input.0.clone(), &input.1), None);
+        assert_eq!(
+            Day06::try_part2(&input),
+            Err("the guard never leaves the grid".to_string())
+        );
+    }
 }
@@ -1,67 +1,361 @@
-use std::iter::repeat_n;
-use rayon::prelude::*;
+use std::fmt;
 
-use itertools::Itertools;
+use rayon::prelude::*;
 
+use crate::parse::{self, ParseError};
+use crate::solution::{Answer, Example, Solution};
 
 #[derive(Debug, PartialEq)]
-struct Equation {
+pub struct Equation {
     target: usize,
     operands: Vec<usize>,
 }
-#[derive(Debug, Clone, Copy)]
-enum Operator {
-    Add,
-    Multiply,
-    Concat
+
+/// An operator between two operands of an equation, which are evaluated
+/// from left to right. New operators only need to implement this trait.
+pub trait Operator: Sync {
+    /// How the operator is selected on the command line.
+    fn name(&self) -> &'static str;
+
+    /// How the operator is written in an equation.
+    fn symbol(&self) -> &'static str;
+
+    /// `None` if the result is not defined, e.g. because it overflows.
+    fn apply(&self, left: usize, right: usize) -> Option<usize>;
+
+    /// All left operands that give `result` with `right`, so that equations
+    /// can be solved backwards. `None` if they cannot be listed, e.g. because
+    /// every left operand works; the left side is then evaluated forwards.
+    fn undo(&self, result: usize, right: usize) -> Option<Vec<usize>>;
 }
 
-fn parse(input: &str) -> Vec<Equation> {
-    input.lines().map(|line| {
-        let (target, right) = line.split_once(':').unwrap();
-        let operands = right.split_whitespace().map(|x| x.parse().unwrap()).collect();
-        Equation { target: target.parse().unwrap(), operands }
-    }).collect()
+pub struct Add;
+pub struct Multiply;
+pub struct Concat;
+pub struct Subtract;
+pub struct Divide;
+pub struct Xor;
+pub struct Power;
+
+/// All operators that can be selected by name.
+pub const OPERATORS: [&dyn Operator; 7] =
+    [&Add, &Multiply, &Concat, &Subtract, &Divide, &Xor, &Power];
+
+/// Shows the operator's name, so that lists of operators can be compared
+/// and printed.
+impl fmt::Debug for dyn Operator {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.name())
+    }
 }
 
-fn apply(in1 : usize, in2 : usize, operator : Operator) -> usize
-{
-    match operator {
-        Operator::Add => in1 + in2,
-        Operator::Multiply => in1 * in2,
-        Operator::Concat => 10usize.pow(in2.ilog10()+1)*in1+in2
+impl PartialEq for dyn Operator {
+    fn eq(&self, other: &Self) -> bool {
+        self.name() == other.name()
     }
 }
 
-fn solve_equation(equation: &Equation, operators: Vec<Operator>) -> usize {
-    operators.into_iter().enumerate().fold(equation.operands[0], |acc, (idx, operator)| apply(acc, equation.operands[idx+1], operator))
+impl Eq for dyn Operator {}
+
+pub fn operator(name: &str) -> Option<&'static dyn Operator> {
+    OPERATORS
+        .into_iter()
+        .find(|operator| operator.name() == name)
 }
 
-fn is_solvable(equation : &Equation, operators : &[Operator]) -> bool {
-    repeat_n(operators.iter().copied(), equation.operands.len()-1).multi_cartesian_product().any(|operator_combination| solve_equation(equation, operator_combination) == equation.target)
+fn parse(input: &str) -> Result<Vec<Equation>, ParseError> {
+    input
+        .lines()
+        .map(|line| {
+            let (target, right) = parse::split_once(input, line, ":")?;
+            let operands: Vec<_> = right
+                .split_whitespace()
+                .map(|x| parse::number(input, x))
+                .collect::<Result<_, _>>()?;
+            if operands.is_empty() {
+                return Err(ParseError::after(
+                    input,
+                    line,
+                    "expected at least one operand",
+                ));
+            }
+            Ok(Equation {
+                target: parse::number(input, target)?,
+                operands,
+            })
+        })
+        .collect()
 }
 
-fn solve_1(equations: &[Equation]) -> usize
-{
-    let operators = vec![Operator::Add, Operator::Multiply,];
-    equations.iter().filter(|eq|is_solvable(eq, &operators)).map(|eq|eq.target).sum()
+/// The number of decimal digits, `0` has one.
+fn digits(number: usize) -> u32 {
+    number.checked_ilog10().unwrap_or(0) + 1
 }
 
-fn solve_2(equations: &[Equation]) -> usize
-{
-    let operators = vec![Operator::Add, Operator::Multiply, Operator::Concat];
-    equations.par_iter().filter(|eq|is_solvable(eq, &operators)).map(|eq|eq.target).sum()
+impl Operator for Add {
+    fn name(&self) -> &'static str {
+        "add"
+    }
+    fn symbol(&self) -> &'static str {
+        "+"
+    }
+
+    fn apply(&self, left: usize, right: usize) -> Option<usize> {
+        left.checked_add(right)
+    }
+
+    fn undo(&self, result: usize, right: usize) -> Option<Vec<usize>> {
+        Some(result.checked_sub(right).into_iter().collect())
+    }
 }
 
-pub fn solve(input: &str) {
-    println!("{}", solve_1(&parse(input)));
-    println!("{}", solve_2(&parse(input)));
+impl Operator for Multiply {
+    fn name(&self) -> &'static str {
+        "mul"
+    }
+    fn symbol(&self) -> &'static str {
+        "*"
+    }
+
+    fn apply(&self, left: usize, right: usize) -> Option<usize> {
+        left.checked_mul(right)
+    }
+
+    fn undo(&self, result: usize, right: usize) -> Option<Vec<usize>> {
+        match right {
+            0 if result == 0 => None,
+            0 => Some(Vec::new()),
+            _ => Some(
+                result
+                    .is_multiple_of(right)
+                    .then(|| result / right)
+                    .into_iter()
+                    .collect(),
+            ),
+        }
+    }
 }
 
+impl Operator for Concat {
+    fn name(&self) -> &'static str {
+        "concat"
+    }
+    fn symbol(&self) -> &'static str {
+        "||"
+    }
 
-#[cfg(test)]
-mod tests {
-    use super::*;
+    fn apply(&self, left: usize, right: usize) -> Option<usize> {
+        10usize
+            .checked_pow(digits(right))
+            .and_then(|shift| left.checked_mul(shift))
+            .and_then(|shifted| shifted.checked_add(right))
+    }
+
+    fn undo(&self, result: usize, right: usize) -> Option<Vec<usize>> {
+        let left = 10usize
+            .checked_pow(digits(right))
+            .filter(|shift| result % shift == right)
+            .map(|shift| result / shift);
+        Some(left.into_iter().collect())
+    }
+}
+
+impl Operator for Subtract {
+    fn name(&self) -> &'static str {
+        "sub"
+    }
+    fn symbol(&self) -> &'static str {
+        "-"
+    }
+
+    fn apply(&self, left: usize, right: usize) -> Option<usize> {
+        left.checked_sub(right)
+    }
+
+    fn undo(&self, result: usize, right: usize) -> Option<Vec<usize>> {
+        Some(result.checked_add(right).into_iter().collect())
+    }
+}
+
+/// Largest divisor whose candidate left operands are listed when solving
+/// backwards, above it division is evaluated forwards.
+const MAX_LISTED_DIVISOR: usize = 16;
+
+impl Operator for Divide {
+    fn name(&self) -> &'static str {
+        "div"
+    }
+    fn symbol(&self) -> &'static str {
+        "/"
+    }
+
+    fn apply(&self, left: usize, right: usize) -> Option<usize> {
+        left.checked_div(right)
+    }
+
+    fn undo(&self, result: usize, right: usize) -> Option<Vec<usize>> {
+        if right == 0 {
+            return Some(Vec::new());
+        }
+        if right > MAX_LISTED_DIVISOR {
+            return None;
+        }
+        let Some(lowest) = result.checked_mul(right) else {
+            return Some(Vec::new());
+        };
+        Some((lowest..=lowest.saturating_add(right - 1)).collect())
+    }
+}
+
+impl Operator for Xor {
+    fn name(&self) -> &'static str {
+        "xor"
+    }
+    fn symbol(&self) -> &'static str {
+        "^"
+    }
+
+    fn apply(&self, left: usize, right: usize) -> Option<usize> {
+        Some(left ^ right)
+    }
+
+    fn undo(&self, result: usize, right: usize) -> Option<Vec<usize>> {
+        Some(vec![result ^ right])
+    }
+}
+
+impl Operator for Power {
+    fn name(&self) -> &'static str {
+        "pow"
+    }
+    fn symbol(&self) -> &'static str {
+        "**"
+    }
+
+    fn apply(&self, left: usize, right: usize) -> Option<usize> {
+        left.checked_pow(u32::try_from(right).ok()?)
+    }
+
+    fn undo(&self, result: usize, right: usize) -> Option<Vec<usize>> {
+        if right == 0 {
+            return (result != 1).then(Vec::new);
+        }
+        // the floating point root is close, the exact one has to be nearby
+        let root = (result as f64).powf(1.0 / right as f64).round() as usize;
+        Some(
+            (root.saturating_sub(1)..=root + 1)
+                .filter(|&left| self.apply(left, right) == Some(result))
+                .collect(),
+        )
+    }
+}
+
+/// Evaluates the equation from left to right, `None` if an operator fails.
+fn evaluate(equation: &Equation, operators: &[&dyn Operator]) -> Option<usize> {
+    operators
+        .iter()
+        .zip(&equation.operands[1..])
+        .try_fold(equation.operands[0], |left, (operator, &right)| {
+            operator.apply(left, right)
+        })
+}
+
+/// Tries all operators between `operands` from left to right until `accept`
+/// takes the value, pushing the chosen operators to `chosen`.
+fn find_forwards<'a>(
+    value: usize,
+    operands: &[usize],
+    operators: &[&'a dyn Operator],
+    accept: &dyn Fn(usize) -> bool,
+    chosen: &mut Vec<&'a dyn Operator>,
+) -> bool {
+    let Some((&next, rest)) = operands.split_first() else {
+        return accept(value);
+    };
+
+    operators.iter().any(|&operator| {
+        let Some(value) = operator.apply(value, next) else {
+            return false;
+        };
+        chosen.push(operator);
+        let solved = find_forwards(value, rest, operators, accept, chosen);
+        if !solved {
+            chosen.pop();
+        }
+        solved
+    })
+}
+
+/// Undoes the operator before the last of `operands` so that they give
+/// `result`, pushing the chosen operators to `chosen` from the last one
+/// backwards.
+fn undo_last<'a>(
+    result: usize,
+    operands: &[usize],
+    operators: &[&'a dyn Operator],
+    chosen: &mut Vec<&'a dyn Operator>,
+) -> bool {
+    let Some((&last, rest)) = operands.split_last() else {
+        return false;
+    };
+    if rest.is_empty() {
+        return result == last;
+    }
+
+    operators.iter().any(|&operator| {
+        chosen.push(operator);
+        let solved = match operator.undo(result, last) {
+            Some(lefts) => lefts
+                .into_iter()
+                .any(|left| undo_last(left, rest, operators, chosen)),
+            None => {
+                let mut forwards = Vec::new();
+                let accept = |left| operator.apply(left, last) == Some(result);
+                let found = find_forwards(rest[0], &rest[1..], operators, &accept, &mut forwards);
+                chosen.extend(forwards.into_iter().rev());
+                found
+            }
+        };
+        if !solved {
+            chosen.pop();
+        }
+        solved
+    })
+}
+
+/// Finds the operators that make the equation true, working backwards from
+/// the target: each operator is only undone where that is possible, which
+/// prunes most combinations early.
+pub fn solve<'a>(
+    equation: &Equation,
+    operators: &[&'a dyn Operator],
+) -> Option<Vec<&'a dyn Operator>> {
+    let mut chosen = Vec::new();
+    if !undo_last(equation.target, &equation.operands, operators, &mut chosen) {
+        return None;
+    }
+    chosen.reverse();
+    debug_assert_eq!(evaluate(equation, &chosen), Some(equation.target));
+    Some(chosen)
+}
+
+/// Writes the equation with the operators filled in, e.g. `190 = 10 * 19`.
+pub fn format_solution(equation: &Equation, operators: &[&dyn Operator]) -> String {
+    let mut text = format!("{} = {}", equation.target, equation.operands[0]);
+    for (operator, operand) in operators.iter().zip(&equation.operands[1..]) {
+        text.push_str(&format!(" {} {operand}", operator.symbol()));
+    }
+    text
+}
+
+/// The sum of the targets of the equations that the operators solve.
+pub fn total(equations: &[Equation], operators: &[&dyn Operator]) -> usize {
+    equations
+        .par_iter()
+        .filter(|eq| solve(eq, operators).is_some())
+        .map(|eq| eq.target)
+        .sum()
+}
 
 const EXAMPLE: &str = "190: 10 19
 3267: 81 40 27
@@ -73,34 +367,257 @@ const EXAMPLE: &str = "190: 10 19
 21037: 9 7 18 13
 292: 11 6 16 20";
 
+pub struct Day07;
+
+impl Solution for Day07 {
+    type Input = Vec<Equation>;
+
+    const EXAMPLES: &'static [Example] = &[Example {
+        input: EXAMPLE,
+        part1: Some("3749"),
+        part2: Some("11387"),
+    }];
+
+    fn parse(input: &str) -> Result<Vec<Equation>, ParseError> {
+        parse(input)
+    }
+
+    fn part1(equations: &Vec<Equation>) -> Option<Answer> {
+        Some(total(equations, &[&Add, &Multiply]).into())
+    }
+
+    fn part2(equations: &Vec<Equation>) -> Option<Answer> {
+        Some(total(equations, &[&Add, &Multiply, &Concat]).into())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
     fn example_equations() -> Vec<Equation> {
         vec![
-            Equation { target: 190, operands: vec![10, 19] },
-            Equation { target: 3267, operands: vec![81, 40, 27] },
-            Equation { target: 83, operands: vec![17, 5] },
-            Equation { target: 156, operands: vec![15, 6] },
-            Equation { target: 7290, operands: vec![6, 8, 6, 15] },
-            Equation { target: 161011, operands: vec![16, 10, 13] },
-            Equation { target: 192, operands: vec![17, 8, 14] },
-            Equation { target: 21037, operands: vec![9, 7, 18, 13] },
-            Equation { target: 292, operands: vec![11, 6, 16, 20] },
+            Equation {
+                target: 190,
+                operands: vec![10, 19],
+            },
+            Equation {
+                target: 3267,
+                operands: vec![81, 40, 27],
+            },
+            Equation {
+                target: 83,
+                operands: vec![17, 5],
+            },
+            Equation {
+                target: 156,
+                operands: vec![15, 6],
+            },
+            Equation {
+                target: 7290,
+                operands: vec![6, 8, 6, 15],
+            },
+            Equation {
+                target: 161011,
+                operands: vec![16, 10, 13],
+            },
+            Equation {
+                target: 192,
+                operands: vec![17, 8, 14],
+            },
+            Equation {
+                target: 21037,
+                operands: vec![9, 7, 18, 13],
+            },
+            Equation {
+                target: 292,
+                operands: vec![11, 6, 16, 20],
+            },
         ]
     }
 
-
     #[test]
     fn parse_example() {
-        assert_eq!(parse(EXAMPLE), example_equations());
+        assert_eq!(parse(EXAMPLE), Ok(example_equations()));
+    }
+
+    #[test]
+    fn parse_invalid() {
+        let err = parse("190: 10 19\n83 17 5").unwrap_err();
+        assert_eq!((err.line, err.column), (2, 1));
+        assert_eq!(parse("190:").unwrap_err().column, 5);
+    }
+
+    fn names(operators: &[&dyn Operator]) -> Vec<&'static str> {
+        operators.iter().map(|operator| operator.name()).collect()
+    }
+
+    #[test]
+    fn test_solve() {
+        let equations = example_equations();
+        let operators: [&dyn Operator; 3] = [&Add, &Multiply, &Concat];
+        assert_eq!(
+            solve(&equations[1], &operators).as_deref().map(names),
+            Some(vec!["mul", "add"])
+        );
+        assert_eq!(
+            solve(&equations[4], &operators).as_deref().map(names),
+            Some(vec!["mul", "concat", "mul"])
+        );
+        assert!(solve(&equations[2], &operators).is_none());
+        for equation in &equations {
+            if let Some(chosen) = solve(equation, &operators) {
+                assert_eq!(evaluate(equation, &chosen), Some(equation.target));
+            }
+        }
+
+        let single = Equation {
+            target: 7,
+            operands: vec![7],
+        };
+        assert_eq!(
+            solve(&single, &operators).map(|chosen| chosen.len()),
+            Some(0)
+        );
+        assert_eq!(
+            format_solution(&equations[4], &solve(&equations[4], &operators).unwrap()),
+            "7290 = 6 * 8 || 6 * 15"
+        );
+    }
+
+    #[test]
+    fn test_overflow() {
+        assert_eq!(Concat.apply(12, 345), Some(12345));
+        assert_eq!(Concat.apply(12, 0), Some(120));
+        assert_eq!(Concat.apply(usize::MAX / 10, 12), None);
+        assert_eq!(Concat.apply(1, usize::MAX), None);
+        assert_eq!(Concat.undo(12345, 345), Some(vec![12]));
+        assert_eq!(Concat.undo(12345, 45), Some(vec![123]));
+        assert_eq!(Concat.undo(12345, 44), Some(vec![]));
+    }
+
+    #[test]
+    fn test_more_operators() {
+        for operator in OPERATORS {
+            for (left, right) in [
+                (0, 0),
+                (1, 0),
+                (7, 0),
+                (17, 3),
+                (3, 17),
+                (1000, 2),
+                (255, 8),
+                (7, 1_000_000_000),
+            ] {
+                let Some(result) = operator.apply(left, right) else {
+                    continue;
+                };
+                if let Some(lefts) = operator.undo(result, right) {
+                    assert!(lefts.contains(&left), "{} {left} {right}", operator.name());
+                    assert!(lefts
+                        .iter()
+                        .all(|&other| operator.apply(other, right) == Some(result)));
+                }
+            }
+        }
+
+        assert_eq!(Divide.undo(5, 0), Some(vec![]));
+        assert_eq!(Divide.undo(0, 1_000_000_000), None);
+        let equation = Equation {
+            target: 5,
+            operands: vec![0, 0],
+        };
+        assert!(solve(&equation, &[&Divide]).is_none());
+        let equation = Equation {
+            target: 3,
+            operands: vec![7, 1_000_000_000],
+        };
+        assert!(solve(&equation, &[&Divide]).is_none());
+        let equation = Equation {
+            target: 0,
+            operands: vec![7, 1_000_000_000],
+        };
+        assert_eq!(
+            solve(&equation, &[&Divide]).as_deref().map(names),
+            Some(vec!["div"])
+        );
+
+        let equation = Equation {
+            target: 4,
+            operands: vec![9, 3, 2, 7],
+        };
+        assert!(solve(&equation, &[&Add, &Multiply]).is_none());
+        let chosen = solve(&equation, &[&Subtract, &Divide, &Xor]).unwrap();
+        assert_eq!(evaluate(&equation, &chosen), Some(4));
+
+        // every left side works for `x ** 0 = 1`, which is evaluated forwards
+        let equation = Equation {
+            target: 1,
+            operands: vec![5, 3, 0],
+        };
+        assert_eq!(
+            solve(&equation, &[&Subtract, &Power]).as_deref().map(names),
+            Some(vec!["sub", "pow"])
+        );
+    }
+
+    // a new operator only has to implement the trait
+    #[test]
+    fn test_custom_operator() {
+        struct Max;
+
+        impl Operator for Max {
+            fn name(&self) -> &'static str {
+                "max"
+            }
+            fn symbol(&self) -> &'static str {
+                "max"
+            }
+
+            fn apply(&self, left: usize, right: usize) -> Option<usize> {
+                Some(left.max(right))
+            }
+
+            fn undo(&self, result: usize, right: usize) -> Option<Vec<usize>> {
+                match result.cmp(&right) {
+                    std::cmp::Ordering::Less => Some(Vec::new()),
+                    std::cmp::Ordering::Equal => Some((0..=right).collect()),
+                    std::cmp::Ordering::Greater => Some(vec![result]),
+                }
+            }
+        }
+
+        let equation = Equation {
+            target: 9,
+            operands: vec![4, 5, 2],
+        };
+        assert_eq!(
+            solve(&equation, &[&Add, &Max]).as_deref().map(names),
+            Some(vec!["add", "max"])
+        );
     }
 
     #[test]
     fn test_solve_1() {
-        assert_eq!(solve_1(&parse(EXAMPLE)), 3749);
+        assert_eq!(total(&parse(EXAMPLE).unwrap(), &[&Add, &Multiply]), 3749);
     }
-    
+
     #[test]
     fn test_solve_2() {
-        assert_eq!(solve_2(&parse(EXAMPLE)), 11387);
+        assert_eq!(
+            total(&parse(EXAMPLE).unwrap(), &[&Add, &Multiply, &Concat]),
+            11387
+        );
     }
 
+    #[test]
+    fn test_operator() {
+        assert_eq!(operator("xor").map(|operator| operator.symbol()), Some("^"));
+        assert!(operator("mod").is_none());
+        assert_eq!(operator("mul"), Some(&Multiply as &dyn Operator));
+        assert_eq!(
+            format!("{:?}", [&Add as &dyn Operator, &Power]),
+            "[add, pow]"
+        );
+    }
 }
@@ -1,18 +1,28 @@
-use std::collections::{HashMap, HashSet};
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::ops::{Bound, RangeBounds, RangeInclusive};
+
 use itertools::Itertools;
 
+use crate::parse::{self, ParseError};
+use crate::solution::{Answer, Example, Solution};
+
 type Coordinate = (i64, i64);
 
 #[derive(Debug, Clone, PartialEq, Eq)]
-struct City {
+pub struct City {
     antenna: HashMap<Coordinate, char>,
     width: usize,
     height: usize,
 }
 
 impl City {
-    fn new(input: &str) -> Self {
-        let lines: Vec<&str> = input.lines().collect();
+    fn new(input: &str) -> Result<Self, ParseError> {
+        let lines = parse::grid_lines(input)?;
+        let is_valid = |c: char| c == '\n' || c == '.' || c.is_ascii_alphanumeric();
+        if let Some((index, c)) = input.char_indices().find(|&(_, c)| !is_valid(c)) {
+            let text = &input[index..index + c.len_utf8()];
+            return Err(ParseError::new(input, text, "expected `.` or an antenna"));
+        }
 
         let antenna = input
             .lines()
@@ -25,52 +35,113 @@ impl City {
             })
             .collect();
 
-        City {
+        Ok(City {
             antenna,
             width: lines[0].len(),
             height: lines.len(),
+        })
+    }
+
+    /// The antennas of each frequency.
+    pub fn frequencies(&self) -> BTreeMap<char, Vec<Coordinate>> {
+        let mut frequencies: BTreeMap<_, Vec<_>> = BTreeMap::new();
+        for (&coordinate, &frequency) in &self.antenna {
+            frequencies.entry(frequency).or_default().push(coordinate);
         }
+        for antennas in frequencies.values_mut() {
+            antennas.sort_unstable();
+        }
+        frequencies
+    }
+
+    /// The harmonics `k` out of `harmonics` that put `from + delta * k`
+    /// inside the city, the points on a line through it are contiguous.
+    fn harmonics_inside(&self, from: Coordinate, delta: Coordinate, harmonics: &impl RangeBounds<i64>) -> RangeInclusive<i64> {
+        let mut low = match harmonics.start_bound() {
+            Bound::Included(&k) => k,
+            Bound::Excluded(&k) => k.saturating_add(1),
+            Bound::Unbounded => i64::MIN,
+        };
+        let mut high = match harmonics.end_bound() {
+            Bound::Included(&k) => k,
+            Bound::Excluded(&k) => k.saturating_sub(1),
+            Bound::Unbounded => i64::MAX,
+        };
+
+        for (start, step, size) in [(from.0, delta.0, self.width as i64), (from.1, delta.1, self.height as i64)] {
+            // 0 <= start + step * k <= size - 1
+            let (first, last) = match step.signum() {
+                0 if (0..size).contains(&start) => continue,
+                0 => return RangeInclusive::new(1, 0),
+                1 => (ceil_div(-start, step), floor_div(size - 1 - start, step)),
+                _ => (ceil_div(size - 1 - start, step), floor_div(-start, step)),
+            };
+            low = low.max(first);
+            high = high.min(last);
+        }
+
+        low..=high
+    }
+}
+
+fn floor_div(dividend: i64, divisor: i64) -> i64 {
+    let quotient = dividend / divisor;
+    if dividend % divisor != 0 && (dividend < 0) != (divisor < 0) {
+        quotient - 1
+    } else {
+        quotient
     }
 }
 
-fn mark_antinodes(city: &City, range: impl IntoIterator<Item = i64> + Clone) -> HashSet<Coordinate> {
-    city.antenna
+fn ceil_div(dividend: i64, divisor: i64) -> i64 {
+    -floor_div(-dividend, divisor)
+}
+
+/// The antinodes of one frequency: for each pair of antennas the points
+/// `k` times their distance behind the second one, for every harmonic `k`.
+fn antinodes_of(city: &City, antennas: &[Coordinate], harmonics: &impl RangeBounds<i64>) -> HashSet<Coordinate> {
+    antennas
         .iter()
         .permutations(2)
-        .filter(|pair| {
-            let [left, right] = &pair[..] else {unreachable!()};
-            left.1 == right.1
-        })
         .flat_map(|pair| {
-            let (left, right) = (pair[0], pair[1]);
-            let (left_x , left_y) = left.0;
-            let (right_x, right_y) = right.0;
-
+            let ((left_x, left_y), (right_x, right_y)) = (*pair[0], *pair[1]);
             let (delta_x, delta_y) = (right_x - left_x, right_y - left_y);
 
-            range.clone().into_iter()
-                .map(move |idx| {
-                    let new_x = right_x + delta_x * idx;
-                    let new_y = right_y + delta_y * idx;
-                    (new_x, new_y)
-                })
-                .take_while(|&(x,y)| x >= 0 && y >= 0 && x < city.width as i64 && y < city.height as i64)
+            city.harmonics_inside((right_x, right_y), (delta_x, delta_y), harmonics)
+                .map(move |k| (right_x + delta_x * k, right_y + delta_y * k))
         })
         .collect()
 }
 
-pub fn solve(input: &str) {
-    let city = City::new(input);
+pub fn antinodes_by_frequency(city: &City, harmonics: impl RangeBounds<i64>) -> BTreeMap<char, HashSet<Coordinate>> {
+    city.frequencies()
+        .into_iter()
+        .map(|(frequency, antennas)| (frequency, antinodes_of(city, &antennas, &harmonics)))
+        .collect()
+}
 
-    println!("{}", mark_antinodes(&city, [1]).len());
-    println!("{}", mark_antinodes(&city, 0..).len());
+/// The antinodes of all frequencies, where those of several frequencies
+/// coincide they count once.
+pub fn antinodes(city: &City, harmonics: impl RangeBounds<i64>) -> HashSet<Coordinate> {
+    antinodes_by_frequency(city, harmonics).into_values().flatten().collect()
 }
 
-#[cfg(test)]
-mod tests {
-    use super::*;
+/// Draws the city with `#` at the antinodes, antennas are drawn on top.
+pub fn render(city: &City, antinodes: &HashSet<Coordinate>) -> String {
+    (0..city.height as i64)
+        .map(|y| {
+            (0..city.width as i64)
+                .map(|x| match city.antenna.get(&(x, y)) {
+                    Some(&frequency) => frequency,
+                    None if antinodes.contains(&(x, y)) => '#',
+                    None => '.',
+                })
+                .collect::<String>()
+        })
+        .join("\n")
+}
 
-    const EXAMPLE: &str = "............
+const EXAMPLE: &str = "............
 ........0...
 .....0......
 .......0....
@@ -83,6 +154,34 @@ mod tests {
 ............
 ............";
 
+pub struct Day08;
+
+impl Solution for Day08 {
+    type Input = City;
+
+    const EXAMPLES: &'static [Example] = &[Example {
+        input: EXAMPLE,
+        part1: Some("14"),
+        part2: Some("34"),
+    }];
+
+    fn parse(input: &str) -> Result<City, ParseError> {
+        City::new(input)
+    }
+
+    fn part1(city: &City) -> Option<Answer> {
+        Some(antinodes(city, 1..=1).len().into())
+    }
+
+    fn part2(city: &City) -> Option<Answer> {
+        Some(antinodes(city, 0..).len().into())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
     fn example_locations() -> City {
         let mut grid: HashMap<Coordinate, char> = HashMap::new();
         grid.insert((8, 1), '0');
@@ -101,16 +200,65 @@ mod tests {
 
     #[test]
     fn test_parse() {
-        assert_eq!(City::new(EXAMPLE), example_locations());
+        assert_eq!(City::new(EXAMPLE), Ok(example_locations()));
+        assert_eq!(City::new("..\n.#").unwrap_err().column, 2);
     }
 
     #[test]
     fn test_1() {
-        assert_eq!(mark_antinodes(&City::new(EXAMPLE), 1..2).len(), 14);
+        assert_eq!(antinodes(&City::new(EXAMPLE).unwrap(), 1..2).len(), 14);
     }
 
     #[test]
     fn test_2() {
-        assert_eq!(mark_antinodes(&City::new(EXAMPLE), 0..12).len(), 34);
+        assert_eq!(antinodes(&City::new(EXAMPLE).unwrap(), 0..12).len(), 34);
+        assert_eq!(antinodes(&City::new(EXAMPLE).unwrap(), ..).len(), 34);
+    }
+
+    #[test]
+    fn test_frequencies() {
+        let city = City::new(EXAMPLE).unwrap();
+        assert_eq!(city.frequencies()[&'A'], [(6, 5), (8, 8), (9, 9)]);
+
+        let counts: Vec<_> = antinodes_by_frequency(&city, 1..=1)
+            .into_iter()
+            .map(|(frequency, antinodes)| (frequency, antinodes.len()))
+            .collect();
+        assert_eq!(counts, [('0', 10), ('A', 5)]);
+    }
+
+    #[test]
+    fn test_harmonic_ranges() {
+        let city = City::new("a.a...\n......").unwrap();
+        let row = |harmonics: (Bound<i64>, Bound<i64>)| {
+            let mut xs: Vec<_> = antinodes(&city, harmonics).into_iter().map(|(x, _)| x).collect();
+            xs.sort_unstable();
+            xs
+        };
+        assert_eq!(row((Bound::Included(1), Bound::Included(1))), [4]);
+        assert_eq!(row((Bound::Included(2), Bound::Unbounded)), []);
+        assert_eq!(row((Bound::Excluded(-1), Bound::Excluded(2))), [0, 2, 4]);
+        assert_eq!(row((Bound::Unbounded, Bound::Unbounded)), [0, 2, 4]);
+        assert_eq!(row((Bound::Included(-1), Bound::Included(-1))), [0, 2]);
+    }
+
+    #[test]
+    fn test_render() {
+        let city = City::new(EXAMPLE).unwrap();
+        assert_eq!(
+            render(&city, &antinodes(&city, 1..=1)),
+            "......#....#
+...#....0...
+....#0....#.
+..#....0....
+....0....#..
+.#....A.....
+...#........
+#......#....
+........A...
+.........A..
+..........#.
+..........#."
+        );
     }
 }
@@ -1,125 +1,285 @@
-use itertools::Itertools;
-
-fn check_sum(disk: &[i64]) -> usize {
-    disk.iter()
-        .enumerate()
-        .filter(|&(_, content)| *content != -1)
-        .map(|(idx, content)| idx * (*content as usize))
-        .sum()
+use std::cmp::Reverse;
+use std::collections::{BinaryHeap, HashSet};
+use std::fmt;
+
+use crate::parse::ParseError;
+use crate::solution::{Answer, Example, Solution};
+
+/// `length` blocks of the file `id` starting at block `start`, a fragmented
+/// file has several of them.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct File {
+    pub id: usize,
+    pub start: usize,
+    pub length: usize,
 }
 
-fn expand(disk: &str) -> Vec<i64> {
-    let mut disk_parsed: Vec<usize> = disk
-        .chars()
-        .map(|a| a.to_digit(10).unwrap() as usize)
-        .collect();
-
-    disk_parsed.push(0);
-
-    disk_parsed
-        .iter()
-        .tuples()
-        .enumerate()
-        .flat_map(|(idx, (file_len, free_space_len))| {
-            std::iter::repeat_n(idx as i64, *file_len)
-                .chain(std::iter::repeat_n(-1 as i64, *free_space_len))
-        })
-        .collect()
+/// A run of `length` free blocks starting at block `start`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Free {
+    pub start: usize,
+    pub length: usize,
 }
 
-fn move_disk(disk: &Vec<i64>) -> Vec<i64> {
-    let mut disk_copy = disk.clone();
+/// `length` blocks of file `id` moved from block `from` to block `to`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Move {
+    pub id: usize,
+    pub from: usize,
+    pub to: usize,
+    pub length: usize,
+}
 
-    let mut removed_elem_ctr = 0;
-    let disk_length = disk_copy.len();
+impl fmt::Display for Move {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let plural = if self.length == 1 { "" } else { "s" };
+        write!(
+            f,
+            "file {}: {} block{plural} from {} to {}",
+            self.id, self.length, self.from, self.to
+        )
+    }
+}
+
+/// E.g. `4 moves of 3 files, 9 blocks`.
+pub fn summarize(moves: &[Move]) -> String {
+    let files: HashSet<_> = moves.iter().map(|step| step.id).collect();
+    let blocks: usize = moves.iter().map(|step| step.length).sum();
+    format!(
+        "{} moves of {} files, {blocks} blocks",
+        moves.len(),
+        files.len()
+    )
+}
 
-    for i in 0..disk_length {
-        while *disk_copy.last().unwrap() == -1 {
-            disk_copy.pop();
-            removed_elem_ctr += 1;
+/// The files and free runs of a disk, both ordered by their start.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Disk {
+    pub files: Vec<File>,
+    pub free: Vec<Free>,
+    /// The number of blocks, including free ones at the end.
+    pub size: usize,
+}
+
+impl Disk {
+    /// The disk with `files`, in any order, and free runs in the gaps.
+    fn from_files(mut files: Vec<File>, size: usize) -> Self {
+        files.retain(|file| file.length > 0);
+        files.sort_unstable_by_key(|file| file.start);
+
+        let mut free = Vec::new();
+        let mut position = 0;
+        for file in &files {
+            if file.start > position {
+                free.push(Free {
+                    start: position,
+                    length: file.start - position,
+                });
+            }
+            position = file.start + file.length;
+        }
+        if size > position {
+            free.push(Free {
+                start: position,
+                length: size - position,
+            });
         }
 
-        if disk_copy[i] == -1 {
-            disk_copy.swap_remove(i);
-            removed_elem_ctr += 1;
+        Disk { files, free, size }
+    }
+
+    /// Writes the disk map, alternating the lengths of files and free runs.
+    ///
+    /// Only the layout survives: the files are numbered in the order they
+    /// are found, and a file or free run longer than 9 blocks is split by a
+    /// run of the other kind of length 0.
+    pub fn to_map(&self) -> String {
+        let mut map = String::new();
+        let mut push = |length: usize, is_file: bool| {
+            let mut length = length;
+            while length > 0 {
+                // files are at even positions of the map
+                if map.len() % 2 == usize::from(is_file) {
+                    map.push('0');
+                }
+                let digit = length.min(9);
+                map.push(char::from_digit(digit as u32, 10).unwrap());
+                length -= digit;
+            }
+        };
+
+        let mut position = 0;
+        for file in &self.files {
+            push(file.start - position, false);
+            push(file.length, true);
+            position = file.start + file.length;
         }
+        if self.size > position {
+            push(self.size - position, false);
+        }
+
+        map
+    }
 
-        if i + removed_elem_ctr + 1 == disk_length {
-            break;
+    /// Draws each block as the id of its file like `00...111...2`, `None` if
+    /// there are ids with more than one digit.
+    pub fn render(&self) -> Option<String> {
+        let mut blocks = vec!['.'; self.size];
+        for file in &self.files {
+            let digit = char::from_digit(u32::try_from(file.id).ok()?, 10)?;
+            blocks[file.start..file.start + file.length].fill(digit);
         }
+        Some(blocks.into_iter().collect())
     }
 
-    disk_copy
+    /// The sum of each block's position times the id of its file.
+    pub fn checksum(&self) -> usize {
+        self.files
+            .iter()
+            .map(|file| {
+                // the sum of the positions start..start + length
+                let positions = file.length * file.start + file.length * (file.length - 1) / 2;
+                file.id * positions
+            })
+            .sum()
+    }
 }
 
-fn fragment_disk(disk: &Vec<i64>) -> Vec<i64> {
-    let mut disk_copy = disk.clone();
+fn parse(disk: &str) -> Result<Disk, ParseError> {
+    let mut files = Vec::new();
+    let mut position = 0;
+
+    for (index, c) in disk.char_indices() {
+        let length = c.to_digit(10).ok_or_else(|| {
+            ParseError::new(disk, &disk[index..index + c.len_utf8()], "expected a digit")
+        })? as usize;
+        if index % 2 == 0 {
+            files.push(File {
+                id: index / 2,
+                start: position,
+                length,
+            });
+        }
+        position += length;
+    }
 
-    let mut end_idx = disk_copy.len() - 1;
-    let mut begin_idx: usize = 0;
+    Ok(Disk::from_files(files, position))
+}
 
-    let mut file_length_ctr = 0;
-    let mut last_file_id = 0;
+/// Moves single blocks from the end of the disk to the leftmost free block,
+/// which fragments the files.
+pub fn move_blocks(disk: &Disk) -> (Disk, Vec<Move>) {
+    let mut files = disk.files.clone();
+    let mut moved = Vec::new();
+    let mut moves = Vec::new();
+
+    'free: for free in &disk.free {
+        let mut free = *free;
+        while free.length > 0 {
+            let Some(last) = files.last_mut().filter(|last| last.start > free.start) else {
+                break 'free;
+            };
+
+            let length = free.length.min(last.length);
+            moves.push(Move {
+                id: last.id,
+                from: last.start + last.length - length,
+                to: free.start,
+                length,
+            });
+            moved.push(File {
+                id: last.id,
+                start: free.start,
+                length,
+            });
+            free.start += length;
+            free.length -= length;
+            last.length -= length;
+            if last.length == 0 {
+                files.pop();
+            }
+        }
+    }
 
-    while end_idx > 0 {
-        if file_length_ctr > 0 {
-            while begin_idx < end_idx {
-                while disk_copy[begin_idx] != -1 {
-                    begin_idx += 1;
-                    if begin_idx > end_idx {
-                        break;
-                    }
-                }
+    files.extend(moved);
+    (Disk::from_files(files, disk.size), moves)
+}
 
-                let mut free_space_ctr = 0;
-                while disk_copy[begin_idx] == -1 {
-                    free_space_ctr += 1;
-                    begin_idx += 1; //begin_idx bos hanenin sagini gösteriyor
-                    if begin_idx > end_idx {
-                        break;
-                    }
-                }
+/// Moves whole files, from the highest id down, to the leftmost free run
+/// they fit in.
+///
+/// The free runs are kept in a min-heap of starts per length, so finding the
+/// leftmost one that fits only looks at the heads of the heaps. A file only
+/// moves left, past the files with lower ids, so the space it leaves is never
+/// needed again.
+pub fn move_files(disk: &Disk) -> (Disk, Vec<Move>) {
+    let longest = disk.free.iter().map(|free| free.length).max().unwrap_or(0);
+    let mut free_by_length = vec![BinaryHeap::new(); longest + 1];
+    for free in &disk.free {
+        free_by_length[free.length].push(Reverse(free.start));
+    }
 
-                if free_space_ctr >= file_length_ctr {
-                    for idx in 0..file_length_ctr {
-                        disk_copy[begin_idx + idx - free_space_ctr] = last_file_id;
-                        disk_copy[end_idx + idx + 1] = -1;
-                    }
-                    break;
-                }
-            }
+    let mut files = disk.files.clone();
+    let mut moves = Vec::new();
+    for file in files.iter_mut().rev() {
+        let leftmost = (file.length.max(1)..=longest)
+            .filter_map(|length| {
+                free_by_length[length]
+                    .peek()
+                    .map(|&Reverse(start)| (start, length))
+            })
+            .min()
+            .filter(|&(start, _)| start < file.start);
+        let Some((start, length)) = leftmost else {
+            continue;
+        };
+
+        free_by_length[length].pop();
+        if length > file.length {
+            free_by_length[length - file.length].push(Reverse(start + file.length));
         }
+        moves.push(Move {
+            id: file.id,
+            from: file.start,
+            to: start,
+            length: file.length,
+        });
+        file.start = start;
+    }
 
-        while end_idx > 0 && disk_copy[end_idx] == -1 {
-            end_idx -= 1;
-        }
+    (Disk::from_files(files, disk.size), moves)
+}
 
-        last_file_id = disk_copy[end_idx];
+const EXAMPLE_INPUT: &str = "2333133121414131402";
 
-        file_length_ctr = 0;
+pub struct Day09;
 
-        while end_idx > 0 && disk_copy[end_idx] == last_file_id {
-            end_idx -= 1; // en sonki dosyanin solunu gösteriyor
-            file_length_ctr += 1;
-        }
+impl Solution for Day09 {
+    type Input = Disk;
+
+    const EXAMPLES: &'static [Example] = &[Example {
+        input: EXAMPLE_INPUT,
+        part1: Some("1928"),
+        part2: Some("2858"),
+    }];
 
-        begin_idx = 0;
+    fn parse(input: &str) -> Result<Disk, ParseError> {
+        parse(input)
     }
 
-    disk_copy
-}
+    fn part1(disk: &Disk) -> Option<Answer> {
+        Some(move_blocks(disk).0.checksum().into())
+    }
 
-pub fn solve(input: &str) {
-    println!("{}", check_sum(&move_disk(&expand(input))));
-    println!("{}", check_sum(&fragment_disk(&expand(input))));
+    fn part2(disk: &Disk) -> Option<Answer> {
+        Some(move_files(disk).0.checksum().into())
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
 
-    const EXAMPLE_INPUT: &str = "2333133121414131402";
-
     fn get_disk_image() -> Vec<i64> {
         [
             0, 0, -1, -1, -1, 1, 1, 1, -1, -1, -1, 2, -1, -1, -1, 3, 3, 3, -1, 4, 4, -1, 5, 5, 5,
@@ -143,23 +303,200 @@ mod tests {
         .to_vec()
     }
 
+    /// The file id of every block, `-1` for free ones.
+    fn blocks(disk: &Disk) -> Vec<i64> {
+        let mut blocks = vec![-1; disk.size];
+        for file in &disk.files {
+            blocks[file.start..file.start + file.length].fill(file.id as i64);
+        }
+        blocks
+    }
+
+    fn check_sum(disk: &[i64]) -> usize {
+        disk.iter()
+            .enumerate()
+            .filter(|&(_, content)| *content != -1)
+            .map(|(idx, content)| idx * (*content as usize))
+            .sum()
+    }
+
     #[test]
-    fn test_expand() {
-        assert_eq!(expand(&EXAMPLE_INPUT), get_disk_image());
+    fn test_parse() {
+        let disk = parse(EXAMPLE_INPUT).unwrap();
+        assert_eq!(blocks(&disk), get_disk_image());
+        assert_eq!(
+            disk.files[1],
+            File {
+                id: 1,
+                start: 5,
+                length: 3
+            }
+        );
+        assert_eq!(
+            disk.free[0],
+            Free {
+                start: 2,
+                length: 3
+            }
+        );
+        assert_eq!(parse("23x3").unwrap_err().column, 3);
     }
 
     #[test]
     fn test_check_sum() {
         assert_eq!(check_sum(&get_moved_disk()), 1928);
+        assert_eq!(
+            move_blocks(&parse(EXAMPLE_INPUT).unwrap()).0.checksum(),
+            1928
+        );
     }
 
     #[test]
     fn test_move() {
-        assert_eq!(get_moved_disk(), move_disk(&get_disk_image()));
+        let mut moved = blocks(&move_blocks(&parse(EXAMPLE_INPUT).unwrap()).0);
+        moved.truncate(get_moved_disk().len());
+        assert_eq!(moved, get_moved_disk());
     }
 
     #[test]
     fn test_fragment() {
-        assert_eq!(fragment_disk(&get_disk_image()), get_fragment_disk());
+        let (disk, _) = move_files(&parse(EXAMPLE_INPUT).unwrap());
+        assert_eq!(blocks(&disk), get_fragment_disk());
+        assert_eq!(disk.checksum(), 2858);
+        assert_eq!(disk.free.iter().map(|free| free.length).sum::<usize>(), 14);
+    }
+
+    #[test]
+    fn test_to_map() {
+        let disk = parse(EXAMPLE_INPUT).unwrap();
+        assert_eq!(disk.to_map(), EXAMPLE_INPUT);
+        assert_eq!(parse("12340").unwrap().to_map(), "1234");
+
+        let (moved, _) = move_files(&disk);
+        assert_eq!(moved.to_map(), "20201030312134414542");
+        assert_eq!(blocks(&parse(&moved.to_map()).unwrap()).len(), moved.size);
+        let renumbered = parse(&moved.to_map()).unwrap();
+        assert_eq!(
+            renumbered
+                .files
+                .iter()
+                .map(|file| (file.start, file.length))
+                .collect::<Vec<_>>(),
+            moved
+                .files
+                .iter()
+                .map(|file| (file.start, file.length))
+                .collect::<Vec<_>>()
+        );
+
+        let long = Disk::from_files(
+            vec![File {
+                id: 0,
+                start: 12,
+                length: 10,
+            }],
+            22,
+        );
+        assert_eq!(long.to_map(), "0903901");
+        assert_eq!(parse("0903901").unwrap().free, long.free);
+    }
+
+    #[test]
+    fn test_render() {
+        let disk = parse(EXAMPLE_INPUT).unwrap();
+        assert_eq!(
+            disk.render().as_deref(),
+            Some("00...111...2...333.44.5555.6666.777.888899")
+        );
+        assert_eq!(
+            move_blocks(&disk).0.render().as_deref(),
+            Some("0099811188827773336446555566..............")
+        );
+        assert_eq!(
+            move_files(&disk).0.render().as_deref(),
+            Some("00992111777.44.333....5555.6666.....8888..")
+        );
+        assert_eq!(parse("1010101010101010101010").unwrap().render(), None);
+    }
+
+    #[test]
+    fn test_moves() {
+        let disk = parse(EXAMPLE_INPUT).unwrap();
+        let (_, moves) = move_files(&disk);
+        assert_eq!(
+            moves[0],
+            Move {
+                id: 9,
+                from: 40,
+                to: 2,
+                length: 2
+            }
+        );
+        assert_eq!(moves[0].to_string(), "file 9: 2 blocks from 40 to 2");
+        assert_eq!(moves[3].to_string(), "file 2: 1 block from 11 to 4");
+        assert_eq!(summarize(&moves), "4 moves of 4 files, 8 blocks");
+
+        let (_, moves) = move_blocks(&disk);
+        assert_eq!(summarize(&moves), "7 moves of 4 files, 12 blocks");
+    }
+
+    /// Moves whole files block by block, scanning from the start every time.
+    fn move_files_slowly(mut blocks: Vec<i64>) -> Vec<i64> {
+        let last_id = blocks.iter().copied().max().unwrap_or(-1);
+        for id in (0..=last_id).rev() {
+            let start = blocks.iter().position(|&block| block == id).unwrap();
+            let length = blocks.iter().filter(|&&block| block == id).count();
+            let target = (0..start).find(|&target| {
+                blocks[target..target + length]
+                    .iter()
+                    .all(|&block| block == -1)
+            });
+            if let Some(target) = target {
+                blocks[target..target + length].fill(id);
+                blocks[start..start + length].fill(-1);
+            }
+        }
+        blocks
+    }
+
+    fn generated_input(files: usize) -> String {
+        (0..files * 2 - 1)
+            .map(|index| {
+                let digit = (index * 7 + index / 3) % 10;
+                // files are never empty
+                let digit = if index % 2 == 0 { digit.max(1) } else { digit };
+                char::from_digit(digit as u32, 10).unwrap()
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_generated() {
+        let disk = parse(&generated_input(500)).unwrap();
+        assert_eq!(
+            blocks(&move_files(&disk).0),
+            move_files_slowly(blocks(&disk))
+        );
+        assert_eq!(move_blocks(&disk).0.checksum(), {
+            let mut moved = blocks(&disk);
+            let free = moved.iter().filter(|&&block| block == -1).count();
+            let mut end = moved.len();
+            for index in 0..moved.len() - free {
+                if moved[index] == -1 {
+                    end -= 1;
+                    while moved[end] == -1 {
+                        end -= 1;
+                    }
+                    moved.swap(index, end);
+                }
+            }
+            check_sum(&moved)
+        });
+
+        // tens of thousands of files
+        let disk = parse(&generated_input(50_000)).unwrap();
+        let (moved, _) = move_files(&disk);
+        assert_eq!(moved.files.len(), 50_000);
+        assert!(moved.checksum() < disk.checksum());
     }
 }
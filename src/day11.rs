@@ -2,18 +2,21 @@ use std::collections::HashMap;
 
 use rayon::prelude::*;
 
+use crate::parse::{self, ParseError};
+use crate::solution::{Answer, Example, Solution};
+
 fn apply_rule(current_stone: usize) -> Vec<usize> {
     let str = current_stone.to_string();
     let str_len = str.chars().count();
 
     if current_stone == 0 {
-        return [1].to_vec();
-    } else if str_len % 2 == 0 {
+        [1].to_vec()
+    } else if str_len.is_multiple_of(2) {
         let mid = str_len / 2;
         let (first_half, second_half) = str.split_at(mid);
-        return [first_half.parse().unwrap(), second_half.parse().unwrap()].to_vec();
+        [first_half.parse().unwrap(), second_half.parse().unwrap()].to_vec()
     } else {
-        return [current_stone * 2024].to_vec();
+        [current_stone * 2024].to_vec()
     }
 }
 
@@ -44,8 +47,8 @@ fn next_generation_cached(
 
 
 
-fn manipulate_stone(initial_state: &Vec<usize>, iteration_count : usize) -> usize {
-    let initial_state = initial_state.clone();
+fn manipulate_stone(initial_state: &[usize], iteration_count : usize) -> usize {
+    let initial_state = initial_state.to_vec();
     initial_state
         .par_iter()
         .map(|&stone| next_generation_cached(stone, iteration_count, &mut HashMap::new()))
@@ -53,10 +56,37 @@ fn manipulate_stone(initial_state: &Vec<usize>, iteration_count : usize) -> usiz
 }
 
 
-pub fn solve(_input: &str) {
-    let v = vec![2, 54, 992917, 5270417, 2514, 28561, 0, 990];
-    println!("{}", manipulate_stone(&v, 25));
-    println!("{}", manipulate_stone(&v, 75));
+fn parse(input: &str) -> Result<Vec<usize>, ParseError> {
+    input
+        .split_whitespace()
+        .map(|stone| parse::number(input, stone))
+        .collect()
+}
+
+const EXAMPLE: &str = "125 17";
+
+pub struct Day11;
+
+impl Solution for Day11 {
+    type Input = Vec<usize>;
+
+    const EXAMPLES: &'static [Example] = &[Example {
+        input: EXAMPLE,
+        part1: Some("55312"),
+        part2: None,
+    }];
+
+    fn parse(input: &str) -> Result<Vec<usize>, ParseError> {
+        parse(input)
+    }
+
+    fn part1(stones: &Vec<usize>) -> Option<Answer> {
+        Some(manipulate_stone(stones, 25).into())
+    }
+
+    fn part2(stones: &Vec<usize>) -> Option<Answer> {
+        Some(manipulate_stone(stones, 75).into())
+    }
 }
 
 #[cfg(test)]
@@ -67,6 +97,12 @@ mod tests {
         vec![125, 17]
     }
 
+    #[test]
+    fn test_parse() {
+        assert_eq!(parse(EXAMPLE), Ok(get_example()));
+        assert_eq!(parse("125 1x7").unwrap_err().column, 5);
+    }
+
     #[test]
     fn test_get_stone_state() {
         assert_eq!(manipulate_stone(&get_example(), 25), 55312);
@@ -1,129 +1,23 @@
 use std::collections::HashSet;
 
-struct Grid {
-    width: usize,
-    height: usize,
-    data: Vec<char>,
-}
-
-impl Grid {
-    fn new(input: &str) -> Self {
-        let lines: Vec<&str> = input.lines().collect();
-        let height = lines.len();
-        let width = lines[0].len();
-        let data = lines.iter().flat_map(|line| line.chars()).collect();
-
-        Grid {
-            width,
-            height,
-            data,
-        }
-    }
+use crate::grid::{Coord, Grid};
+use crate::parse::ParseError;
+use crate::solution::{Answer, Example, Solution};
 
-    fn get(&self, position: &Position) -> Option<char> {
-        if (position.row as usize) < self.height && (position.col as usize) < self.width {
-            Some(self.data[(position.row * self.width as i64 + position.col) as usize])
-        } else {
-            None
-        }
-    }
-}
-
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
-struct Position {
-    row: i64,
-    col: i64,
-}
-
-enum Direction {
-    NorthWest,
-    North,
-    NorthEast,
-    East,
-    SouthEast,
-    South,
-    SouthWest,
-    West,
-}
-
-impl Position {
-    fn get_direct_neighbors(&self) -> HashSet<Position> {
-        [
-            (self.row.checked_sub(1), Some(self.col)),
-            (self.row.checked_add(1), Some(self.col)),
-            (Some(self.row), self.col.checked_sub(1)),
-            (Some(self.row), self.col.checked_add(1)),
-        ]
-        .into_iter()
-        .filter_map(|(row, col)| {
-            Some(Position {
-                row: row?,
-                col: col?,
-            })
-        })
-        .collect()
-    }
-
-    fn get_neighbor(&self, direction: Direction) -> Position {
-        match direction {
-            Direction::NorthWest => Position {
-                row: self.row - 1,
-                col: self.col - 1,
-            },
-            Direction::North => Position {
-                row: self.row - 1,
-                col: self.col,
-            },
-            Direction::NorthEast => Position {
-                row: self.row - 1,
-                col: self.col + 1,
-            },
-            Direction::East => Position {
-                row: self.row,
-                col: self.col + 1,
-            },
-            Direction::SouthEast => Position {
-                row: self.row + 1,
-                col: self.col + 1,
-            },
-            Direction::South => Position {
-                row: self.row + 1,
-                col: self.col,
-            },
-            Direction::SouthWest => Position {
-                row: self.row + 1,
-                col: self.col - 1,
-            },
-            Direction::West => Position {
-                row: self.row,
-                col: self.col - 1,
-            },
-        }
-    }
-}
-
-fn segment(grid: &Grid) -> Vec<HashSet<Position>> {
-    let mut unvisited_pos: HashSet<_> = (0..grid.data.len())
-        .map(|index| {
-            let row = (index / grid.width) as i64;
-            let col = (index % grid.width) as i64;
-
-            Position { row, col }
-        })
-        .collect();
+fn segment(grid: &Grid<char>) -> Vec<HashSet<Coord>> {
+    let mut unvisited_pos: HashSet<_> = grid.coords().collect();
 
     let mut segments = Vec::new();
 
     while let Some(seed) = unvisited_pos.iter().next() {
         let mut segment = HashSet::new();
-        let segment_name = grid.get(seed).unwrap();
+        let segment_name = grid.get(*seed);
 
         let mut todo = vec![*seed];
         while let Some(pos) = todo.pop() {
-            if grid.get(&pos) == Some(segment_name) {
+            if grid.get(pos) == segment_name {
                 todo.extend(
-                    pos.get_direct_neighbors()
-                        .into_iter()
+                    grid.neighbours4(pos)
                         .filter(|neighbor| !segment.contains(neighbor)),
                 );
                 segment.insert(pos);
@@ -137,80 +31,66 @@ fn segment(grid: &Grid) -> Vec<HashSet<Position>> {
     segments
 }
 
-fn calculate_perimeter(group: &HashSet<Position>) -> usize {
+fn calculate_perimeter(group: &HashSet<Coord>) -> usize {
     group
         .iter()
-        .map(|&pos| {
-            4 - pos
-                .get_direct_neighbors()
-                .intersection(&group)
-                .cloned()
-                .count()
-        })
+        .map(|&pos| 4 - pos.neighbours4().filter(|pos| group.contains(pos)).count())
         .sum()
 }
 
-fn total_price(grid: &Grid) -> usize {
+fn total_price(grid: &Grid<char>) -> usize {
     segment(grid)
         .iter()
-        .map(|segment| segment.len() * calculate_perimeter(&segment))
+        .map(|segment| segment.len() * calculate_perimeter(segment))
         .sum()
 }
 
-fn count_corners(segment: &HashSet<Position>) -> usize {
+fn count_corners(segment: &HashSet<Coord>) -> usize {
     segment
         .iter()
-        .map(|cur| {
+        .map(|&cur| {
             let mut sum = 0;
 
-            if !segment.contains(&cur.get_neighbor(Direction::North))
-                && !segment.contains(&cur.get_neighbor(Direction::West))
-            {
+            if !segment.contains(&(cur + Coord::UP)) && !segment.contains(&(cur + Coord::LEFT)) {
                 sum += 1;
             }
 
-            if !segment.contains(&cur.get_neighbor(Direction::North))
-                && !segment.contains(&cur.get_neighbor(Direction::East))
-            {
+            if !segment.contains(&(cur + Coord::UP)) && !segment.contains(&(cur + Coord::RIGHT)) {
                 sum += 1;
             }
 
-            if !segment.contains(&cur.get_neighbor(Direction::South))
-                && !segment.contains(&cur.get_neighbor(Direction::West))
-            {
+            if !segment.contains(&(cur + Coord::DOWN)) && !segment.contains(&(cur + Coord::LEFT)) {
                 sum += 1;
             }
 
-            if !segment.contains(&cur.get_neighbor(Direction::South))
-                && !segment.contains(&cur.get_neighbor(Direction::East))
-            {
+            if !segment.contains(&(cur + Coord::DOWN)) && !segment.contains(&(cur + Coord::RIGHT)) {
                 sum += 1;
             }
 
-            if segment.contains(&cur.get_neighbor(Direction::North))
-                && segment.contains(&cur.get_neighbor(Direction::West))
-                && !segment.contains(&cur.get_neighbor(Direction::NorthWest))
+            if segment.contains(&(cur + Coord::UP))
+                && segment.contains(&(cur + Coord::LEFT))
+                && !segment.contains(&(cur + Coord::UP_LEFT))
             {
                 sum += 1;
             }
 
-            if segment.contains(&cur.get_neighbor(Direction::North))
-                && segment.contains(&cur.get_neighbor(Direction::East))
-                && !segment.contains(&cur.get_neighbor(Direction::NorthEast))
+            if segment.contains(&(cur + Coord::UP))
+                && segment.contains(&(cur + Coord::RIGHT))
+                && !segment.contains(&(cur + Coord::UP_RIGHT))
             {
                 sum += 1;
             }
 
-            if segment.contains(&cur.get_neighbor(Direction::South))
-                && segment.contains(&cur.get_neighbor(Direction::West))
-                && !segment.contains(&cur.get_neighbor(Direction::SouthWest))
+            if segment.contains(&(cur + Coord::DOWN))
+                && segment.contains(&(cur + Coord::LEFT))
+                && !segment.contains(&(cur + Coord::DOWN_LEFT))
             {
                 sum += 1;
             }
 
-            if segment.contains(&cur.get_neighbor(Direction::South))
-                && segment.contains(&cur.get_neighbor(Direction::East))
-                && !segment.contains(&cur.get_neighbor(Direction::SouthEast))
+            if segment.contains(&(cur + Coord::DOWN))
+                && segment.contains(&(cur + Coord::RIGHT))
+                && !segment.contains(&(cur + Coord::DOWN_RIGHT))
             {
                 sum += 1;
             }
@@ -219,52 +99,70 @@ fn count_corners(segment: &HashSet<Position>) -> usize {
         .sum()
 }
 
-fn total_price_bulk_discount(grid: &Grid) -> usize {
+fn total_price_bulk_discount(grid: &Grid<char>) -> usize {
     segment(grid)
         .iter()
-        .map(|segment| segment.len() * count_corners(&segment))
+        .map(|segment| segment.len() * count_corners(segment))
         .sum()
 }
 
-pub fn solve(input: &str) {
-    let grid = Grid::new(input);
-    println!("{}", total_price(&grid));
-    println!("{}", total_price_bulk_discount(&grid));
+const EXAMPLE: &str = "AAAA
+BBCD
+BBCC
+EEEC";
+
+pub struct Day12;
+
+impl Solution for Day12 {
+    type Input = Grid<char>;
+
+    const EXAMPLES: &'static [Example] = &[Example {
+        input: EXAMPLE,
+        part1: Some("140"),
+        part2: Some("80"),
+    }];
+
+    fn parse(input: &str) -> Result<Grid<char>, ParseError> {
+        Grid::parse(input)
+    }
+
+    fn part1(grid: &Grid<char>) -> Option<Answer> {
+        Some(total_price(grid).into())
+    }
+
+    fn part2(grid: &Grid<char>) -> Option<Answer> {
+        Some(total_price_bulk_discount(grid).into())
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
 
-    const EXAMPLE: &str = "AAAA
-BBCD
-BBCC
-EEEC";
-
     const EXAMPLE2: &str = "AAAA";
 
     #[test]
     fn test_example() {
-        let grid = Grid::new(EXAMPLE);
+        let grid = Grid::parse(EXAMPLE).unwrap();
         assert_eq!(total_price(&grid), 140);
     }
 
     #[test]
     fn test_segment() {
-        let grid = Grid::new(EXAMPLE);
+        let grid = Grid::parse(EXAMPLE).unwrap();
         assert_eq!(segment(&grid).len(), 5);
     }
 
     #[test]
     fn test_calculate_perimeter() {
-        let grid = Grid::new(EXAMPLE2);
+        let grid = Grid::parse(EXAMPLE2).unwrap();
         let segment = segment(&grid);
-        assert_eq!(calculate_perimeter(&segment.iter().next().unwrap()), 10);
+        assert_eq!(calculate_perimeter(segment.first().unwrap()), 10);
     }
 
     #[test]
     fn test_bulk_discount() {
-        let grid = Grid::new(EXAMPLE);
+        let grid = Grid::parse(EXAMPLE).unwrap();
         assert_eq!(total_price_bulk_discount(&grid), 80);
     }
 }
@@ -2,6 +2,9 @@ use std::collections::HashSet;
 
 use itertools::Itertools;
 
+use crate::parse::{self, ParseError};
+use crate::solution::{Answer, Example, Solution};
+
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
 struct Position {
     x: i64,
@@ -15,30 +18,38 @@ struct Velocity {
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
-struct Robot {
+pub struct Robot {
     pos: Position,
     vel: Velocity,
 }
 
-impl Position {
-    fn apply_restrictions(&mut self) {
-        while self.x < 0 {
-            self.x += 101; //width
-        }
-
-        while self.y < 0 {
-            self.y += 103; //height
-        }
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct Room {
+    width: i64,
+    height: i64,
+}
 
-        while self.x >= 101 {
-            //width
-            self.x -= 101;
-        }
+const ROOM: Room = Room {
+    width: 101,
+    height: 103,
+};
+
+/// The example of the puzzle description takes place in a smaller room.
+const EXAMPLE_ROOM: Room = Room {
+    width: 11,
+    height: 7,
+};
+
+/// The robots and the room they move in.
+pub struct Robots {
+    room: Room,
+    robots: Vec<Robot>,
+}
 
-        while self.y >= 103 {
-            //height
-            self.y -= 103;
-        }
+impl Position {
+    fn apply_restrictions(&mut self, room: Room) {
+        self.x = self.x.rem_euclid(room.width);
+        self.y = self.y.rem_euclid(room.height);
     }
 
     fn get_neighbors(&self) -> HashSet<Position> {
@@ -80,85 +91,71 @@ impl Position {
 }
 
 impl Robot {
-    fn move_it(&mut self) {
+    fn move_it(&mut self, room: Room) {
         self.pos.x += self.vel.x;
         self.pos.y += self.vel.y;
 
-        self.pos.apply_restrictions()
+        self.pos.apply_restrictions(room)
     }
 
-    fn quadrant(&self) -> usize {
-        if self.pos.x < 101 / 2 && self.pos.y < 103 / 2 {
+    fn quadrant(&self, room: Room) -> usize {
+        let (mid_x, mid_y) = (room.width / 2, room.height / 2);
+
+        if self.pos.x < mid_x && self.pos.y < mid_y {
             return 0;
         }
-        if self.pos.x > 101 / 2 && self.pos.y < 103 / 2 {
+        if self.pos.x > mid_x && self.pos.y < mid_y {
             return 1;
         }
-        if self.pos.x < 101 / 2 && self.pos.y > 103 / 2 {
+        if self.pos.x < mid_x && self.pos.y > mid_y {
             return 2;
         }
-        if self.pos.x > 101 / 2 && self.pos.y > 103 / 2 {
+        if self.pos.x > mid_x && self.pos.y > mid_y {
             return 3;
         }
-        return 4;
+        4
     }
 }
 
-fn print(robots: &[Robot]) {
-    for y in 0..103 {
-        for x in 0..101 {
-            if robots
-                .iter()
-                .any(|robot| robot.pos.x == x && robot.pos.y == y)
-            {
-                print!("X");
-            } else {
-                print!(" ");
-            }
-        }
-        println!("");
-    }
-}
-
-fn parse(input: &str) -> Vec<Robot> {
+fn parse(input: &str) -> Result<Vec<Robot>, ParseError> {
     input
         .lines()
         .map(|line| {
-            let (left, right) = line.split_once(' ').unwrap();
-            let left = left.strip_prefix("p=").unwrap();
-            let (px, py) = left.split_once(',').unwrap();
+            let (left, right) = parse::split_once(input, line, " ")?;
+            let left = parse::strip_prefix(input, left, "p=")?;
+            let (px, py) = parse::split_once(input, left, ",")?;
 
-            let right = right.strip_prefix("v=").unwrap();
-            let (vx, vy) = right.split_once(',').unwrap();
+            let right = parse::strip_prefix(input, right, "v=")?;
+            let (vx, vy) = parse::split_once(input, right, ",")?;
 
-            Robot {
+            Ok(Robot {
                 pos: Position {
-                    x: px.parse().unwrap(),
-                    y: py.parse().unwrap(),
+                    x: parse::number(input, px)?,
+                    y: parse::number(input, py)?,
                 },
                 vel: Velocity {
-                    x: vx.parse().unwrap(),
-                    y: vy.parse().unwrap(),
+                    x: parse::number(input, vx)?,
+                    y: parse::number(input, vy)?,
                 },
-            }
+            })
         })
         .collect()
 }
 
-fn count_quadrants(robots: &[Robot]) -> usize {
+fn count_quadrants(robots: &[Robot], room: Room) -> usize {
     robots
         .iter()
-        .counts_by(Robot::quadrant)
+        .counts_by(|robot| robot.quadrant(room))
         .iter()
         .filter(|&(key, _)| *key != 4)
         .map(|(_, frequency)| frequency)
         .product()
 }
 
-fn move_robots(robots: &mut [Robot], freq: usize) {
+fn move_robots(robots: &mut [Robot], freq: usize, room: Room) {
     for robot in robots {
         for _ in 0..freq {
-            robot.move_it();
+            robot.move_it(room);
         }
     }
 }
@@ -175,48 +172,36 @@ fn is_christmas_tree(robots: &[Robot]) -> bool {
         return true;
     }
 
-    return false;
+    false
 }
 
-fn form_christmas_tree(robots: &mut [Robot]) -> usize {
+fn form_christmas_tree(robots: &mut [Robot], room: Room) -> usize {
     let mut ctr = 0;
 
     while !is_christmas_tree(robots) {
         ctr += 1;
         for robot in &mut *robots {
-            robot.move_it();
+            robot.move_it(room);
         }
     }
 
-    print(robots);
     ctr
 }
 
-fn solve_1(robots: &[Robot]) -> usize {
+fn solve_1(robots: &[Robot], room: Room) -> usize {
     let mut robots = robots.to_vec();
 
-    move_robots(&mut robots, 100);
-    count_quadrants(&robots)
+    move_robots(&mut robots, 100, room);
+    count_quadrants(&robots, room)
 }
 
-fn solve_2(robots: &[Robot]) -> usize {
+fn solve_2(robots: &[Robot], room: Room) -> usize {
     let mut robots_clone = robots.to_vec();
 
-    form_christmas_tree(&mut robots_clone)
+    form_christmas_tree(&mut robots_clone, room)
 }
 
-pub fn solve(input: &str) {
-    let robots = parse(input);
-
-    println!("{}", solve_1(&robots));
-    println!("{}", solve_2(&robots));
-}
-
-#[cfg(test)]
-mod tests {
-    use super::*;
-
-    const EXAMPLE: &str = "p=0,4 v=3,-3
+const EXAMPLE: &str = "p=0,4 v=3,-3
 p=6,3 v=-1,-3
 p=10,3 v=-1,2
 p=2,0 v=2,-1
@@ -229,6 +214,44 @@ p=7,3 v=-1,2
 p=2,4 v=2,-3
 p=9,5 v=-3,-3";
 
+pub struct Day14;
+
+impl Solution for Day14 {
+    type Input = Robots;
+
+    // the robots of the example never form a christmas tree
+    const EXAMPLES: &'static [Example] = &[Example {
+        input: EXAMPLE,
+        part1: Some("12"),
+        part2: None,
+    }];
+
+    fn parse(input: &str) -> Result<Robots, ParseError> {
+        let robots = parse(input)?;
+        Ok(Robots { room: ROOM, robots })
+    }
+
+    fn parse_example(input: &str) -> Result<Robots, ParseError> {
+        let robots = parse(input)?;
+        Ok(Robots {
+            room: EXAMPLE_ROOM,
+            robots,
+        })
+    }
+
+    fn part1(input: &Robots) -> Option<Answer> {
+        Some(solve_1(&input.robots, input.room).into())
+    }
+
+    fn part2(input: &Robots) -> Option<Answer> {
+        Some(solve_2(&input.robots, input.room).into())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
     fn get_example() -> Vec<Robot> {
         vec![
             Robot {
@@ -284,7 +307,10 @@ p=9,5 v=-3,-3";
 
     #[test]
     fn parse_example() {
-        assert_eq!(parse(EXAMPLE), get_example());
+        assert_eq!(parse(EXAMPLE), Ok(get_example()));
+
+        let err = parse("p=0,4 v=3,-3\np=6,3 w=-1,-3").unwrap_err();
+        assert_eq!((err.line, err.column, err.text.as_str()), (2, 7, "w=-1,-3"));
     }
 
     #[test]
@@ -293,7 +319,7 @@ p=9,5 v=-3,-3";
             pos: Position { x: 2, y: 4 },
             vel: Velocity { x: 2, y: -3 },
         };
-        robot.move_it();
+        robot.move_it(ROOM);
 
         assert_eq!(robot.pos, Position { x: 4, y: 1 });
         assert_eq!(robot.vel, Velocity { x: 2, y: -3 });
@@ -302,8 +328,8 @@ p=9,5 v=-3,-3";
     #[test]
     fn test_count_quadrants() {
         let mut robots = get_example();
-        move_robots(&mut robots, 100);
+        move_robots(&mut robots, 100, EXAMPLE_ROOM);
 
-        assert_eq!(count_quadrants(&robots), 12);
+        assert_eq!(count_quadrants(&robots, EXAMPLE_ROOM), 12);
     }
 }
@@ -1,80 +1,10 @@
-use std::collections::HashSet;
+use crate::grid::{Coord, Grid};
+use crate::parse::{self, ParseError};
+use crate::solution::{Answer, Example, Solution};
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
-struct Position {
-    row: usize,
-    col: usize,
-}
-
-impl Position {
-    fn go_to(&self, direction: Direction) -> Position {
-        match direction {
-            Direction::Up => Position {
-                row: self.row - 1,
-                col: self.col,
-            },
-            Direction::Right => Position {
-                row: self.row,
-                col: self.col + 1,
-            },
-            Direction::Down => Position {
-                row: self.row + 1,
-                col: self.col,
-            },
-            Direction::Left => Position {
-                row: self.row,
-                col: self.col - 1,
-            },
-        }
-    }
-}
-
-#[derive(Debug, Clone, PartialEq, Eq, Hash)]
-struct Grid {
-    width: usize,
-    height: usize,
-    data: Vec<char>,
-}
-
-impl Grid {
-    fn new(input: &str) -> Self {
-        let lines: Vec<&str> = input.lines().collect();
-        let height = lines.len();
-        let width = lines[0].len();
-        let data = lines.iter().flat_map(|line| line.chars()).collect();
-
-        Grid {
-            width,
-            height,
-            data,
-        }
-    }
-
-    fn get(&self, position: &Position) -> Option<char> {
-        if position.row < self.height && position.col < self.width {
-            Some(self.data[position.row * self.width + position.col])
-        } else {
-            None
-        }
-    }
-
-    fn set(&mut self, position: &Position, value: char) {
-        self.data[position.row * self.width + position.col] = value;
-    }
-
-    fn print(&self) {
-        for row in 0..self.height {
-            for col in 0..self.width {
-                print!("{}", self.data[row * self.width + col]);
-            }
-            println!();
-        }
-    }
-}
-
-#[derive(Clone)]
-struct Input {
-    map: Grid,
+#[derive(Debug, Clone)]
+pub struct Input {
+    map: Grid<char>,
     sequence: String,
 }
 
@@ -94,73 +24,56 @@ impl Direction {
     fn is_horizontal(self) -> bool {
         self == Direction::Left || self == Direction::Right
     }
+
+    fn offset(self) -> Coord {
+        match self {
+            Direction::Up => Coord::UP,
+            Direction::Right => Coord::RIGHT,
+            Direction::Down => Coord::DOWN,
+            Direction::Left => Coord::LEFT,
+        }
+    }
 }
 
 fn move_boxes_in_direction(
-    grid: &mut Grid,
-    elem_pos: &Position,
+    grid: &mut Grid<char>,
+    elem_pos: Coord,
     direction: Direction,
     elem_to_move: char,
 ) {
-    let grid_original = grid.data.clone();
-
-    let old_pos_idx = elem_pos.row * grid.width + elem_pos.col;
+    let grid_original = grid.clone();
 
-    let new_pos = elem_pos.go_to(direction);
-    let new_pos_idx = new_pos.row * grid.width + new_pos.col;
+    let new_pos = elem_pos + direction.offset();
 
-    if grid.get(&new_pos).unwrap() == '.' {
-        grid.data[new_pos_idx] = elem_to_move;
-        grid.data[old_pos_idx] = '.';
-    } else if grid.get(&new_pos).unwrap() == '#' {
-        return;
-    } else {
-        let new_elem = grid.get(&new_pos).unwrap();
-        move_boxes_in_direction(grid, &new_pos, direction, new_elem);
+    if is_empty(grid, new_pos) {
+        grid.set(new_pos, elem_to_move);
+        grid.set(elem_pos, '.');
+    } else if !is_wall(grid, new_pos) {
+        let new_elem = grid[new_pos];
+        move_boxes_in_direction(grid, new_pos, direction, new_elem);
 
-        if direction == Direction::Up || direction == Direction::Down {
+        if direction.is_vertical() {
             if new_elem == ']' {
-                move_boxes_in_direction(
-                    grid,
-                    &Position {
-                        row: new_pos.row,
-                        col: new_pos.col - 1,
-                    },
-                    direction,
-                    '[',
-                );
+                move_boxes_in_direction(grid, new_pos + Coord::LEFT, direction, '[');
             } else if new_elem == '[' {
-                move_boxes_in_direction(
-                    grid,
-                    &Position {
-                        row: new_pos.row,
-                        col: new_pos.col + 1,
-                    },
-                    direction,
-                    ']',
-                );
+                move_boxes_in_direction(grid, new_pos + Coord::RIGHT, direction, ']');
             }
         }
 
-        if grid.data != grid_original {
-            move_boxes_in_direction(grid, &elem_pos, direction, elem_to_move);
+        if *grid != grid_original {
+            move_boxes_in_direction(grid, elem_pos, direction, elem_to_move);
         }
     }
 }
 
-fn apply_sequence(grid: &Grid, sequence: &str) -> Grid {
+fn apply_sequence(grid: &Grid<char>, sequence: &str) -> Grid<char> {
     let mut grid = grid.clone();
 
     for dir in sequence.chars() {
-        let robot_pos = grid
-            .data
-            .iter()
-            .position(|&c| c == '@')
-            .map(|index| Position {
-                row: index / grid.width,
-                col: index % grid.width,
-            })
-            .unwrap();
+        // without a robot nothing moves at all
+        let Some(robot_pos) = get_robot_pos(&grid) else {
+            break;
+        };
 
         let direction = match dir {
             '^' => Direction::Up,
@@ -169,92 +82,79 @@ fn apply_sequence(grid: &Grid, sequence: &str) -> Grid {
             '<' => Direction::Left,
             _ => panic!("Invalid direction in sequence"),
         };
-        move_boxes_in_direction(&mut grid, &robot_pos, direction, '@');
+        move_boxes_in_direction(&mut grid, robot_pos, direction, '@');
     }
 
     grid
 }
 
-fn is_wall(grid: &Grid, position: &Position) -> bool {
-    grid.get(position).unwrap() == '#'
+fn is_wall(grid: &Grid<char>, position: Coord) -> bool {
+    grid[position] == '#'
 }
 
-fn is_empty(grid: &Grid, position: &Position) -> bool {
-    grid.get(position).unwrap() == '.'
+fn is_empty(grid: &Grid<char>, position: Coord) -> bool {
+    grid[position] == '.'
 }
 
-fn get_robot_pos(grid: &Grid) -> Position {
-    grid.data
-        .iter()
-        .position(|&c| c == '@')
-        .map(|index| Position {
-            row: index / grid.width,
-            col: index % grid.width,
-        })
-        .unwrap()
+fn get_robot_pos(grid: &Grid<char>) -> Option<Coord> {
+    grid.find(&'@')
 }
 
-fn can_it_move(
-    grid: &Grid,
-    initial_pos: &Position,
-    direction: Direction,
-    consider_these: Vec<Position>,
-) -> bool {
+fn can_it_move(grid: &Grid<char>, direction: Direction, consider_these: Vec<Coord>) -> bool {
     let mut is_ok = true;
     for pos in consider_these {
-        let new_pos = pos.go_to(direction);
+        let new_pos = pos + direction.offset();
 
-        if grid.get(&new_pos).unwrap() == '#' {
+        if is_wall(grid, new_pos) {
             is_ok = false;
             break;
         }
 
-        if grid.get(&new_pos).unwrap() == '.' {
+        if is_empty(grid, new_pos) {
             continue;
         }
 
         let mut consider_these_new = Vec::new();
 
-        if grid.get(&new_pos).unwrap() == ']' {
+        if grid[new_pos] == ']' {
             consider_these_new.push(new_pos);
-            consider_these_new.push(new_pos.go_to(Direction::Left));
-        } else if grid.get(&new_pos).unwrap() == '[' {
+            consider_these_new.push(new_pos + Coord::LEFT);
+        } else if grid[new_pos] == '[' {
             consider_these_new.push(new_pos);
-            consider_these_new.push(new_pos.go_to(Direction::Right));
+            consider_these_new.push(new_pos + Coord::RIGHT);
         }
-        is_ok &= can_it_move(grid, &new_pos, direction, consider_these_new);
+        is_ok &= can_it_move(grid, direction, consider_these_new);
     }
 
     is_ok
 }
 
-fn move_robot(grid: &mut Grid, direction: Direction) {
-    let robot_pos = get_robot_pos(grid);
-    if direction == Direction::Left || direction == Direction::Right {
-        move_boxes_in_direction(grid, &robot_pos, direction, '@');
+fn move_robot(grid: &mut Grid<char>, direction: Direction) {
+    let Some(robot_pos) = get_robot_pos(grid) else {
+        return;
+    };
+    if direction.is_horizontal() {
+        move_boxes_in_direction(grid, robot_pos, direction, '@');
         return;
     }
 
     // direction is Up OR Down
-    let old_pos_idx = robot_pos.row * grid.width + robot_pos.col;
-
-    let new_pos = robot_pos.go_to(direction);
-    let new_pos_idx = new_pos.row * grid.width + new_pos.col;
+    let new_pos = robot_pos + direction.offset();
 
     // move robot safely to new position if new position is empty
-    if grid.data[new_pos_idx] == '.' {
-        move_boxes_in_direction(grid, &robot_pos, direction, '@');
+    if is_empty(grid, new_pos) {
+        move_boxes_in_direction(grid, robot_pos, direction, '@');
         return;
-    } else if grid.data[new_pos_idx] == '#' {
+    } else if is_wall(grid, new_pos) {
         return;
     }
 
-    if can_it_move(grid, &robot_pos, direction, vec![robot_pos].to_vec()) {
-        move_boxes_in_direction(grid, &robot_pos, direction, '@');
+    if can_it_move(grid, direction, [robot_pos].to_vec()) {
+        move_boxes_in_direction(grid, robot_pos, direction, '@');
     }
 }
 
-fn apply_sequence_for_extended_map(grid: &Grid, sequence: &str) -> Grid {
+fn apply_sequence_for_extended_map(grid: &Grid<char>, sequence: &str) -> Grid<char> {
     let mut grid = grid.clone();
 
     for dir in sequence.chars() {
@@ -272,45 +172,35 @@ fn apply_sequence_for_extended_map(grid: &Grid, sequence: &str) -> Grid {
     grid
 }
 
-fn calculate_grid(grid: &Grid) -> usize {
-    grid.data
-        .iter()
-        .enumerate()
+fn calculate_grid(grid: &Grid<char>) -> usize {
+    grid.iter()
         .filter(|(_, ch)| **ch == 'O' || **ch == '[')
-        .map(|(index, _)| Position {
-            row: index / grid.width,
-            col: index % grid.width,
-        })
-        .map(|pos| (pos.row) * 100 + pos.col)
+        .map(|(pos, _)| (pos.row * 100 + pos.col) as usize)
         .sum()
 }
 
-fn parse(input: &str) -> Input {
-    let lines: Vec<&str> = input.lines().collect();
-    let grid_lines: Vec<&str> = lines
-        .iter()
-        .take_while(|&&line| !line.is_empty())
-        .cloned()
-        .collect();
-    let sequence_lines: Vec<&str> = lines
-        .iter()
-        .skip_while(|&&line| !line.is_empty())
-        .skip(1)
-        .cloned()
-        .collect();
-    let sequence = sequence_lines.join("");
+fn parse(input: &str) -> Result<Input, ParseError> {
+    // the map is followed by an empty line and the moves, which may span several lines
+    let (grid_input, sequence_input) = input.split_once("\n\n").unwrap_or((input, ""));
+    parse::only_chars(input, grid_input, "#.O[]@")?;
+    if let Some((index, robot)) = grid_input.match_indices('@').nth(1) {
+        return Err(ParseError::new(
+            input,
+            &grid_input[index..index + robot.len()],
+            "expected at most one robot",
+        ));
+    }
+    let map = Grid::parse(grid_input)?;
 
-    let grid_input = grid_lines.join("\n");
-    let map = Grid::new(&grid_input);
+    parse::only_chars(input, sequence_input, "^>v<")?;
+    let sequence = sequence_input.lines().collect();
 
-    Input { map, sequence }
+    Ok(Input { map, sequence })
 }
 
-fn extend(grid: &mut Grid) {
-    grid.width *= 2;
-    grid.data = grid
-        .data
-        .iter()
+fn extend(grid: &mut Grid<char>) {
+    let data = grid
+        .values()
         .flat_map(|&ch| match ch {
             '#' => ['#', '#'],
             'O' => ['[', ']'],
@@ -319,8 +209,7 @@ fn extend(grid: &mut Grid) {
             _ => unreachable!(),
         })
         .collect();
-
-    grid.print();
+    *grid = Grid::from_vec(grid.width() * 2, data);
 }
 
 fn solve_1(input: &Input) -> usize {
@@ -336,10 +225,68 @@ fn solve_2(input: &Input) -> usize {
     calculate_grid(&grid)
 }
 
-pub fn solve(input: &str) {
-    let parsed = parse(input);
-    println!("{}", solve_1(&parsed));
-    println!("{}", solve_2(&parsed));
+const EXAMPLE: &str = "########
+#..O.O.#
+##@.O..#
+#...O..#
+#.#.O..#
+#...O..#
+#......#
+########
+
+<^^>>>vv<v>>v<<";
+
+const LARGE_EXAMPLE: &str = "##########
+#..O..O.O#
+#......O.#
+#.OO..O.O#
+#..O@..O.#
+#O#..O...#
+#O..O..O.#
+#.OO.O.OO#
+#....O...#
+##########
+
+<vv>^<v^>v>^vv^v>v<>v^v<v<^vv<<<^><<><>>v<vvv<>^v^>^<<<><<v<<<v^vv^v>^
+vvv<<^>^v^^><<>>><>^<<><^vv^^<>vvv<>><^^v>^>vv<>v<<<<v<^v>^<^^>>>^<v<v
+><>vv>v^v^<>><>>>><^^>vv>v<^^^>>v^v^<^^>v^^>v^<^v>v<>>v^v^<v>v^^<^^vv<
+<<v<^>>^^^^>>>v^<>vvv^><v<<<>^^^vv^<vvv>^>v<^^^^v<>^>vvvv><>>v^<<^^^^^
+^><^><>>><>^^<<^^v>>><^<v>^<vv>>v>>>^v><>^v><<<<v>>v<v<v>vvv>^<><<>^><
+^>><>^v<><^vvv<^^<><v<<<<<><^v<<<><<<^^<v<^^^><^>>^<v^><<<^>>^v<v^v<v^
+>^>>^v>vv>^<<^v<>><<><<v<<v><>v<^vv<<<>^^v^>^^>>><<^v>>v^v><^^>>^<>vv^
+<><^^>^^^<><vvvvv^v<v<<>^v<v>v<<^><<><<><<<^^<<<^<<>><<><^^^>^^<>^>v<>
+^^>vv<^v^v<vv>^<><v<^v>^^^>>>^^vvv^>vvv<>>>^<^>>>>>^<<^v>^vvv<>^<><<v>
+v^^>>><<^^<>>^v^<v^vv<>v^<<>^<^v^v><^<<<><<^<v><v<>vv>>v><v^<vv<>v^<<^";
+
+pub struct Day15;
+
+impl Solution for Day15 {
+    type Input = Input;
+
+    const EXAMPLES: &'static [Example] = &[
+        Example {
+            input: EXAMPLE,
+            part1: Some("2028"),
+            part2: None,
+        },
+        Example {
+            input: LARGE_EXAMPLE,
+            part1: Some("10092"),
+            part2: Some("9021"),
+        },
+    ];
+
+    fn parse(input: &str) -> Result<Input, ParseError> {
+        parse(input)
+    }
+
+    fn part1(input: &Input) -> Option<Answer> {
+        Some(solve_1(input).into())
+    }
+
+    fn part2(input: &Input) -> Option<Answer> {
+        Some(solve_2(input).into())
+    }
 }
 
 #[cfg(test)]
@@ -352,17 +299,6 @@ mod tests {
 
 <";
 
-    const EXAMPLE: &str = "########
-#..O.O.#
-##@.O..#
-#...O..#
-#.#.O..#
-#...O..#
-#......#
-########
-
-<^^>>>vv<v>>v<<";
-
     const EXAMPLE_END: &str = "########
 #....OO#
 ##.....#
@@ -374,17 +310,6 @@ mod tests {
 
 <^^>>>vv<v>>v<<";
 
-    const LARGE_EXAMPLE: &str = "##########
-#..O..O.O#
-#......O.#
-#.OO..O.O#
-#..O@..O.#
-#O#..O...#
-#O..O..O.#
-#.OO.O.OO#
-#....O...#
-##########";
-
     const EXAMPLE2: &str = "#######
 #...#.#
 #.....#
@@ -466,70 +391,79 @@ v^^>>><<^^<>>^v^<v^vv<>v^<<>^<^v^v><^<<<><<^<v><v<>vv>>v><v^<vv<>v^<<^";
 
     #[test]
     fn parse_example() {
-        let parsed = parse(EXAMPLE);
-        assert_eq!(parsed.map.width, 8);
-        assert_eq!(parsed.map.height, 8);
+        let parsed = parse(EXAMPLE).unwrap();
+        assert_eq!(parsed.map.width(), 8);
+        assert_eq!(parsed.map.height(), 8);
         assert_eq!(parsed.sequence.len(), 15);
     }
 
+    #[test]
+    fn parse_invalid() {
+        let err = parse("####\n#@O#\n####\n\n<<\n>x").unwrap_err();
+        assert_eq!((err.line, err.column, err.text.as_str()), (6, 2, "x"));
+        assert!(parse("####\n#@@#\n####\n\n<<").is_err());
+    }
+
     #[test]
     fn test_move_robot() {
-        let parsed = parse(EXAMPLE);
+        let parsed = parse(EXAMPLE).unwrap();
         let grid = apply_sequence(&parsed.map, &parsed.sequence);
         assert_eq!(calculate_grid(&grid), 2028);
     }
 
     #[test]
     fn test_calculate_grid() {
-        let parsed = parse(SMALL_EXAMPLE);
+        let parsed = parse(SMALL_EXAMPLE).unwrap();
         assert_eq!(calculate_grid(&parsed.map), 104);
 
-        let parsed = parse(EXAMPLE_END);
+        let parsed = parse(EXAMPLE_END).unwrap();
         assert_eq!(calculate_grid(&parsed.map), 2028);
 
-        let parsed = parse(SMALL_EXAMPLE_EXTENDED);
+        let parsed = parse(SMALL_EXAMPLE_EXTENDED).unwrap();
         assert_eq!(calculate_grid(&parsed.map), 105);
 
-        let parsed = parse(LARGE_EXAMPLE_EXTENDED_END);
+        let parsed = parse(LARGE_EXAMPLE_EXTENDED_END).unwrap();
         assert_eq!(calculate_grid(&parsed.map), 9021);
     }
 
     #[test]
     fn test_extend() {
-        let mut parsed = parse(LARGE_EXAMPLE);
+        let mut parsed = parse(LARGE_EXAMPLE).unwrap();
         extend(&mut parsed.map);
 
-        let parsed_extended = parse(LARGE_EXAMPLE_EXTENDED);
+        let parsed_extended = parse(LARGE_EXAMPLE_EXTENDED).unwrap();
         assert_eq!(parsed.map, parsed_extended.map);
     }
 
     #[test]
     fn test_extended_move_robot() {
-        let mut parsed = parse(EXAMPLE2);
+        let mut parsed = parse(EXAMPLE2).unwrap();
         extend(&mut parsed.map);
 
-        let parsed_extended = parse(EXAMPLE2_EXTENDED);
+        let parsed_extended = parse(EXAMPLE2_EXTENDED).unwrap();
         assert_eq!(parsed.map, parsed_extended.map);
 
         let grid = apply_sequence_for_extended_map(&parsed_extended.map, &parsed.sequence);
-        let parsed_extended_end = parse(EXAMPLE2_EXTENDED_END);
+        let parsed_extended_end = parse(EXAMPLE2_EXTENDED_END).unwrap();
         assert_eq!(grid, parsed_extended_end.map);
     }
 
     #[test]
     fn test_large_extended_move_robot() {
-        let parsed = parse(LARGE_EXAMPLE_EXTENDED);
+        let parsed = parse(LARGE_EXAMPLE_EXTENDED).unwrap();
         let grid = apply_sequence_for_extended_map(&parsed.map, &parsed.sequence);
-        let parsed_extended_end = parse(LARGE_EXAMPLE_EXTENDED_END);
+        let parsed_extended_end = parse(LARGE_EXAMPLE_EXTENDED_END).unwrap();
         assert_eq!(grid, parsed_extended_end.map);
     }
 
     #[test]
     fn test_custom() {
-        let parsed = parse(CUSTOM);
+        let parsed = parse(CUSTOM).unwrap();
         let grid = apply_sequence_for_extended_map(&parsed.map, &parsed.sequence);
-        grid.print();
 
-        assert!(false);
+        let count = |grid: &Grid<char>, c: char| grid.values().filter(|&&x| x == c).count();
+        assert_eq!(count(&grid, '['), count(&parsed.map, '['));
+        assert_eq!(count(&grid, ']'), count(&parsed.map, ']'));
+        assert_eq!(count(&grid, '@'), 1);
     }
 }
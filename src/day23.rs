@@ -2,18 +2,16 @@ use std::collections::{HashMap, HashSet};
 
 use itertools::Itertools;
 
+use crate::parse::{self, ParseError};
+use crate::solution::{Answer, Example, Solution};
+
 #[derive(Debug)]
-struct Connections(HashMap<String, Vec<String>>);
+pub struct Connections(HashMap<String, Vec<String>>);
 
-fn parse(input: &str) -> Vec<(&str, &str)> {
+fn parse(input: &str) -> Result<Vec<(&str, &str)>, ParseError> {
     input
         .lines()
-        .map(|line| {
-            let mut parts = line.split('-');
-            let first_number = parts.next().unwrap();
-            let second_number = parts.next().unwrap();
-            (first_number, second_number)
-        })
+        .map(|line| parse::split_once(input, line, "-"))
         .collect()
 }
 
@@ -34,7 +32,7 @@ fn get_all_connections(single_connections: &Vec<(&str, &str)>) -> Connections {
             .or_insert_with(|| vec![value.to_string()]);
     }
 
-    return Connections(connections);
+    Connections(connections)
 }
 
 fn get_all_trios_with_t(connections: &Connections) -> usize {
@@ -53,7 +51,7 @@ fn get_all_trios(connections: &Connections) -> HashSet<Vec<String>> {
         for comb in combs {
             if connections.get(comb[0]).unwrap().contains(comb[1]) {
                 let mut trio: Vec<String> =
-                    vec![source.clone(), comb[0].clone(), comb[1].clone()].to_vec();
+                    [source.clone(), comb[0].clone(), comb[1].clone()].to_vec();
                 trio.sort();
                 all_trios.insert(trio);
             }
@@ -93,7 +91,7 @@ fn get_party_of_size_n(connections: &Connections, size: usize) -> String {
         }
     }
 
-    return "".to_string();
+    "".to_string()
 }
 
 fn get_biggest_lan_party(connections: &Connections) -> String {
@@ -108,22 +106,10 @@ fn get_biggest_lan_party(connections: &Connections) -> String {
         largest_party = last_party.clone();
     }
 
-    return largest_party.to_string();
-}
-
-pub fn solve(input: &str) {
-    let single_connections = parse(input);
-    let connections = get_all_connections(&single_connections);
-
-    println!("{}", get_all_trios_with_t(&connections));
-    println!("{}", get_biggest_lan_party(&connections));
+    largest_party.to_string()
 }
 
-#[cfg(test)]
-mod tests {
-    use super::*;
-
-    const EXAMPLE: &str = "kh-tc
+const EXAMPLE: &str = "kh-tc
 qp-kh
 de-cg
 ka-co
@@ -156,6 +142,34 @@ wh-qp
 tb-vc
 td-yn";
 
+pub struct Day23;
+
+impl Solution for Day23 {
+    type Input = Connections;
+
+    const EXAMPLES: &'static [Example] = &[Example {
+        input: EXAMPLE,
+        part1: Some("7"),
+        part2: Some("co,de,ka,ta"),
+    }];
+
+    fn parse(input: &str) -> Result<Connections, ParseError> {
+        Ok(get_all_connections(&parse(input)?))
+    }
+
+    fn part1(connections: &Connections) -> Option<Answer> {
+        Some(get_all_trios_with_t(connections).into())
+    }
+
+    fn part2(connections: &Connections) -> Option<Answer> {
+        Some(get_biggest_lan_party(connections).into())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
     const SHORT_EXAMPLE: &str = "ka-co
 ta-co
 de-co
@@ -165,14 +179,14 @@ ka-de";
 
     #[test]
     fn test_trios() {
-        let single_connections = parse(EXAMPLE);
+        let single_connections = parse(EXAMPLE).unwrap();
         let connections = get_all_connections(&single_connections);
         assert_eq!(7, get_all_trios_with_t(&connections));
     }
 
     #[test]
     fn test_biggest_lan_party_large_input() {
-        let single_connections = parse(EXAMPLE);
+        let single_connections = parse(EXAMPLE).unwrap();
         let connections = get_all_connections(&single_connections);
         let conn = get_biggest_lan_party(&connections);
         assert_eq!(conn, "co,de,ka,ta");
@@ -180,7 +194,7 @@ ka-de";
 
     #[test]
     fn test_biggest_lan_party() {
-        let single_connections = parse(SHORT_EXAMPLE);
+        let single_connections = parse(SHORT_EXAMPLE).unwrap();
         let connections = get_all_connections(&single_connections);
         let conn = get_biggest_lan_party(&connections);
         assert_eq!(conn, "co,de,ka,ta");
@@ -1,12 +1,13 @@
-use std::{collections::HashMap, mem::swap};
+use std::collections::HashMap;
 
-use itertools::Itertools;
+use crate::parse::{self, ParseError};
+use crate::solution::{Answer, Example, Solution};
 
 #[derive(Debug, Clone)]
 enum Operator {
-    XOR,
-    AND,
-    OR,
+    Xor,
+    And,
+    Or,
 }
 
 #[derive(Debug, Clone)]
@@ -18,7 +19,7 @@ struct Operation {
 }
 
 #[derive(Debug, Clone)]
-struct Input {
+pub struct Input {
     wires: HashMap<String, i64>,
     operations: Vec<Operation>,
 }
@@ -46,9 +47,9 @@ fn substitute_variables(input: &mut Input) {
 
 fn apply_gate(op1: i64, op2: i64, operator: Operator) -> i64 {
     match operator {
-        Operator::XOR => op1 ^ op2,
-        Operator::AND => op1 & op2,
-        Operator::OR => op1 | op2,
+        Operator::Xor => op1 ^ op2,
+        Operator::And => op1 & op2,
+        Operator::Or => op1 | op2,
     }
 }
 
@@ -66,9 +67,11 @@ fn apply_operations(input: &mut Input) {
 }
 
 fn calculate_z(input: &Input) -> i64 {
-    (0..46).fold(0i64, |acc, idx| {
-        acc + input.wires.get(&format!("z{idx:02}")).unwrap() * (2i64.pow(idx))
-    })
+    input
+        .wires
+        .iter()
+        .filter_map(|(name, value)| Some((name.strip_prefix('z')?.parse::<u32>().ok()?, value)))
+        .fold(0i64, |acc, (idx, value)| acc + value * (2i64.pow(idx)))
 }
 
 fn calculate(input: Input) -> i64 {
@@ -84,63 +87,53 @@ fn calculate(input: Input) -> i64 {
     calculate_z(&input)
 }
 
-fn parse_input(lines: &str) -> Input {
+fn parse_input(input: &str) -> Result<Input, ParseError> {
     let mut initial_values: HashMap<String, i64> = HashMap::new();
     let mut operations = Vec::new();
 
-    for line in lines.lines() {
-        if let Some((key, value)) = line.split_once(": ") {
-            let value = value.parse().unwrap();
-            initial_values.insert(key.trim().to_string(), value);
-        } else if let Some((part1, result)) = line.split_once(" -> ") {
-            let parts: Vec<&str> = part1.split_whitespace().collect();
-            if parts.len() == 3 {
-                let op = match parts[1] {
-                    "XOR" => Operator::XOR,
-                    "AND" => Operator::AND,
-                    "OR" => Operator::OR,
-                    _ => continue,
-                };
-                let input1 = parts[0].to_string();
-                let input2 = parts[2].to_string();
-                let output = result.trim().to_string();
-                operations.push(Operation {
-                    op1: (input1, -1),
-                    op2: (input2, -1),
-                    operator: op,
-                    result: (output, -1),
-                });
-            }
-        }
-    }
-    Input {
-        wires: initial_values,
-        operations,
-    }
-}
+    let (wires, gates) = parse::split_once(input, input, "\n\n")?;
 
-fn add_numbers_detect_error(input: &Input) -> usize {
-    let mut input = input.clone();
-    for (name, val) in &mut input.wires {
-        *val = 0;
+    for line in wires.lines() {
+        let (key, value) = parse::split_once(input, line, ": ")?;
+        let value = match value {
+            "0" => 0,
+            "1" => 1,
+            _ => return Err(ParseError::new(input, value, "expected `0` or `1`")),
+        };
+        initial_values.insert(key.trim().to_string(), value);
     }
 
-    dbg!(calculate(input));
-
-    3
-}
+    for line in gates.lines() {
+        let (part1, result) = parse::split_once(input, line, " -> ")?;
+        let parts: Vec<&str> = part1.split_whitespace().collect();
+        let [input1, op, input2] = parts[..] else {
+            return Err(ParseError::new(
+                input,
+                part1,
+                "expected a gate like `x00 AND y00`",
+            ));
+        };
+        let op = match op {
+            "XOR" => Operator::Xor,
+            "AND" => Operator::And,
+            "OR" => Operator::Or,
+            _ => return Err(ParseError::new(input, op, "expected `AND`, `OR` or `XOR`")),
+        };
+        operations.push(Operation {
+            op1: (input1.to_string(), -1),
+            op2: (input2.to_string(), -1),
+            operator: op,
+            result: (result.trim().to_string(), -1),
+        });
+    }
 
-pub fn solve(input: &str) {
-    let input: Input = parse_input(input);
-    //println!("{}", calculate(input));
-    println!("{}", add_numbers_detect_error(&input));
+    Ok(Input {
+        wires: initial_values,
+        operations,
+    })
 }
 
-#[cfg(test)]
-mod tests {
-    use super::*;
-
-    const EXAMPLE: &str = "x00: 1
+const EXAMPLE: &str = "x00: 1
 x01: 0
 x02: 1
 x03: 1
@@ -188,35 +181,43 @@ hwm AND bqk -> z03
 tgd XOR rvg -> z12
 tnw OR pbm -> gnj";
 
-    const EXAMPLE2: &str = "x00: 0
-x01: 1
-x02: 0
-x03: 1
-x04: 0
-x05: 1
-y00: 0
-y01: 0
-y02: 1
-y03: 1
-y04: 0
-y05: 1
+pub struct Day24;
+
+impl Solution for Day24 {
+    type Input = Input;
+
+    const EXAMPLES: &'static [Example] = &[Example {
+        input: EXAMPLE,
+        part1: Some("2024"),
+        part2: None,
+    }];
 
-x00 AND y00 -> z05
-x01 AND y01 -> z02
-x02 AND y02 -> z01
-x03 AND y03 -> z03
-x04 AND y04 -> z04
-x05 AND y05 -> z00";
+    fn parse(input: &str) -> Result<Input, ParseError> {
+        parse_input(input)
+    }
+
+    fn try_part1(input: &Input) -> Result<Option<Answer>, String> {
+        let z = calculate(input.clone());
+        let answer = Answer::try_from(z).map_err(|_| format!("z is the negative number {z}"))?;
+        Ok(Some(answer))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
 
     #[test]
     fn parse_example() {
-        let input: Input = parse_input(EXAMPLE);
+        let input: Input = parse_input(EXAMPLE).unwrap();
         assert_eq!(2024, calculate(input));
     }
 
     #[test]
-    fn parse_example_part2() {
-        add_numbers_detect_error(&parse_input(EXAMPLE2));
-        assert!(false);
+    fn parse_invalid() {
+        let err = parse_input("x00: 1\nx01: 2\n\nx00 AND x01 -> z00").unwrap_err();
+        assert_eq!((err.line, err.column, err.text.as_str()), (2, 6, "2"));
+        let err = parse_input("x00: 1\n\nx00 NAND x01 -> z00").unwrap_err();
+        assert_eq!((err.line, err.column, err.text.as_str()), (3, 5, "NAND"));
     }
 }
@@ -1,15 +1,27 @@
 use itertools::{Either, Itertools};
 
+use crate::parse::{self, ParseError};
+use crate::solution::{Answer, Example, Solution};
+
 #[derive(Clone, Debug)]
-struct Key(Vec<usize>);
+pub struct Key(Vec<usize>);
 
 #[derive(Clone, Debug)]
-struct Lock(Vec<usize>);
+pub struct Lock(Vec<usize>);
 
-fn parse(input: &str) -> Vec<Vec<String>> {
+fn parse(input: &str) -> Result<Vec<Vec<String>>, ParseError> {
     input
         .split("\n\n")
-        .map(|schema_str| schema_str.lines().map(|a| a.to_owned()).collect())
+        .map(|schema_str| {
+            parse::only_chars(input, schema_str, "#.")?;
+            if let Some(line) = schema_str.lines().find(|line| line.len() != 5) {
+                return Err(ParseError::new(input, line, "expected a row of width 5"));
+            }
+            if schema_str.lines().count() != 7 {
+                return Err(ParseError::new(input, schema_str, "expected 7 rows"));
+            }
+            Ok(schema_str.lines().map(|a| a.to_owned()).collect())
+        })
         .collect()
 }
 
@@ -48,16 +60,7 @@ fn match_keys(keys: &[Key], locks: &[Lock]) -> usize {
         .count()
 }
 
-pub fn solve(input: &str) {
-    let (keys, locks) = get_schematics(&parse(input));
-    println!("{}", match_keys(&keys, &locks));
-}
-
-#[cfg(test)]
-mod tests {
-    use super::*;
-
-    const EXAMPLE: &str = "#####
+const EXAMPLE: &str = "#####
 .####
 .####
 .####
@@ -97,10 +100,38 @@ mod tests {
 #.#.#
 #####";
 
+pub struct Day25;
+
+impl Solution for Day25 {
+    type Input = (Vec<Key>, Vec<Lock>);
+
+    const EXAMPLES: &'static [Example] = &[Example {
+        input: EXAMPLE,
+        part1: Some("3"),
+        part2: None,
+    }];
+
+    fn parse(input: &str) -> Result<Self::Input, ParseError> {
+        Ok(get_schematics(&parse(input)?))
+    }
+
+    fn part1((keys, locks): &Self::Input) -> Option<Answer> {
+        Some(match_keys(keys, locks).into())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
     #[test]
     fn test_parse() {
-        let input = parse(EXAMPLE);
+        let input = parse(EXAMPLE).unwrap();
         let (keys, locks) = get_schematics(&input);
         assert_eq!(match_keys(&keys, &locks), 3);
+
+        let err =
+            parse("#####\n.####\n.####\n.####\n.#.#.\n.#...\n.....\n\n#####\n##").unwrap_err();
+        assert_eq!((err.line, err.column, err.text.as_str()), (10, 1, "##"));
     }
 }
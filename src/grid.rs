@@ -0,0 +1,334 @@
+use std::fmt;
+use std::ops::{Add, AddAssign, Index, IndexMut, Mul, Neg, Sub};
+
+use crate::parse::{self, ParseError};
+
+/// A position on a grid, or the offset between two positions.
+///
+/// Coordinates are signed so that stepping off the grid yields a position that
+/// is simply not contained in it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord, Default)]
+pub struct Coord {
+    pub row: i64,
+    pub col: i64,
+}
+
+impl Coord {
+    pub const UP: Coord = Coord::new(-1, 0);
+    pub const DOWN: Coord = Coord::new(1, 0);
+    pub const LEFT: Coord = Coord::new(0, -1);
+    pub const RIGHT: Coord = Coord::new(0, 1);
+    pub const UP_LEFT: Coord = Coord::new(-1, -1);
+    pub const UP_RIGHT: Coord = Coord::new(-1, 1);
+    pub const DOWN_LEFT: Coord = Coord::new(1, -1);
+    pub const DOWN_RIGHT: Coord = Coord::new(1, 1);
+
+    /// The four orthogonal directions, clockwise starting with `UP`.
+    pub const ORTHOGONAL: [Coord; 4] = [Coord::UP, Coord::RIGHT, Coord::DOWN, Coord::LEFT];
+
+    /// All eight directions, clockwise starting with `UP`.
+    pub const ALL_DIRECTIONS: [Coord; 8] = [
+        Coord::UP,
+        Coord::UP_RIGHT,
+        Coord::RIGHT,
+        Coord::DOWN_RIGHT,
+        Coord::DOWN,
+        Coord::DOWN_LEFT,
+        Coord::LEFT,
+        Coord::UP_LEFT,
+    ];
+
+    pub const fn new(row: i64, col: i64) -> Self {
+        Coord { row, col }
+    }
+
+    /// Rotates an offset by 90 degrees clockwise, e.g. `UP` becomes `RIGHT`.
+    pub fn turn_right(self) -> Coord {
+        Coord::new(self.col, -self.row)
+    }
+
+    pub fn turn_left(self) -> Coord {
+        Coord::new(-self.col, self.row)
+    }
+
+    pub fn manhattan(self, other: Coord) -> u64 {
+        self.row.abs_diff(other.row) + self.col.abs_diff(other.col)
+    }
+
+    /// The orthogonal neighbours, ignoring any grid bounds.
+    pub fn neighbours4(self) -> impl Iterator<Item = Coord> {
+        Coord::ORTHOGONAL.into_iter().map(move |dir| self + dir)
+    }
+}
+
+impl fmt::Display for Coord {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "({}, {})", self.row, self.col)
+    }
+}
+
+impl Add for Coord {
+    type Output = Coord;
+
+    fn add(self, other: Coord) -> Coord {
+        Coord::new(self.row + other.row, self.col + other.col)
+    }
+}
+
+impl AddAssign for Coord {
+    fn add_assign(&mut self, other: Coord) {
+        *self = *self + other;
+    }
+}
+
+impl Sub for Coord {
+    type Output = Coord;
+
+    fn sub(self, other: Coord) -> Coord {
+        Coord::new(self.row - other.row, self.col - other.col)
+    }
+}
+
+impl Neg for Coord {
+    type Output = Coord;
+
+    fn neg(self) -> Coord {
+        Coord::new(-self.row, -self.col)
+    }
+}
+
+impl Mul<i64> for Coord {
+    type Output = Coord;
+
+    fn mul(self, factor: i64) -> Coord {
+        Coord::new(self.row * factor, self.col * factor)
+    }
+}
+
+/// A rectangular grid stored row by row.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Grid<T> {
+    width: usize,
+    height: usize,
+    data: Vec<T>,
+}
+
+impl<T> Grid<T> {
+    /// Builds a grid from its cells in row-major order.
+    ///
+    /// Panics if `data` does not fill complete rows of `width` cells.
+    pub fn from_vec(width: usize, data: Vec<T>) -> Self {
+        assert!(width > 0 && data.len().is_multiple_of(width), "incomplete grid row");
+
+        Grid {
+            width,
+            height: data.len() / width,
+            data,
+        }
+    }
+
+    /// Parses one cell per character, `cell` returns `None` for characters
+    /// that are not allowed in the grid.
+    pub fn parse_with(
+        input: &str,
+        mut cell: impl FnMut(char) -> Option<T>,
+    ) -> Result<Self, ParseError> {
+        let lines = parse::grid_lines(input)?;
+        let width = lines[0].len();
+
+        let mut data = Vec::with_capacity(width * lines.len());
+        for line in lines {
+            for (index, c) in line.char_indices() {
+                let value = cell(c).ok_or_else(|| {
+                    let text = &line[index..index + c.len_utf8()];
+                    ParseError::new(input, text, "unexpected character")
+                })?;
+                data.push(value);
+            }
+        }
+
+        Ok(Grid::from_vec(width, data))
+    }
+
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    pub fn contains(&self, coord: Coord) -> bool {
+        (0..self.height as i64).contains(&coord.row) && (0..self.width as i64).contains(&coord.col)
+    }
+
+    fn index_of(&self, coord: Coord) -> Option<usize> {
+        self.contains(coord)
+            .then(|| coord.row as usize * self.width + coord.col as usize)
+    }
+
+    fn coord_of(&self, index: usize) -> Coord {
+        Coord::new((index / self.width) as i64, (index % self.width) as i64)
+    }
+
+    pub fn get(&self, coord: Coord) -> Option<&T> {
+        self.index_of(coord).map(|index| &self.data[index])
+    }
+
+    pub fn get_mut(&mut self, coord: Coord) -> Option<&mut T> {
+        self.index_of(coord).map(|index| &mut self.data[index])
+    }
+
+    /// Replaces the cell at `coord` and returns the previous value, or
+    /// `None` (leaving the grid untouched) if `coord` lies outside the grid.
+    pub fn set(&mut self, coord: Coord, value: T) -> Option<T> {
+        self.get_mut(coord)
+            .map(|cell| std::mem::replace(cell, value))
+    }
+
+    /// All coordinates in row-major order.
+    pub fn coords(&self) -> impl Iterator<Item = Coord> + use<T> {
+        let width = self.width as i64;
+        (0..self.height as i64).flat_map(move |row| (0..width).map(move |col| Coord::new(row, col)))
+    }
+
+    /// All cells with their coordinates in row-major order.
+    pub fn iter(&self) -> impl Iterator<Item = (Coord, &T)> {
+        self.data
+            .iter()
+            .enumerate()
+            .map(|(index, value)| (self.coord_of(index), value))
+    }
+
+    pub fn values(&self) -> impl Iterator<Item = &T> {
+        self.data.iter()
+    }
+
+    pub fn rows(&self) -> impl Iterator<Item = &[T]> {
+        self.data.chunks(self.width)
+    }
+
+    /// The orthogonal neighbours of `coord` that lie on the grid.
+    pub fn neighbours4(&self, coord: Coord) -> impl Iterator<Item = Coord> + '_ {
+        coord
+            .neighbours4()
+            .filter(|&neighbour| self.contains(neighbour))
+    }
+}
+
+impl<T: PartialEq> Grid<T> {
+    /// The first coordinate (in row-major order) holding `value`.
+    pub fn find(&self, value: &T) -> Option<Coord> {
+        self.data
+            .iter()
+            .position(|cell| cell == value)
+            .map(|index| self.coord_of(index))
+    }
+
+    pub fn find_all<'a>(&'a self, value: &'a T) -> impl Iterator<Item = Coord> + 'a {
+        self.iter()
+            .filter(move |&(_, cell)| cell == value)
+            .map(|(coord, _)| coord)
+    }
+}
+
+impl<T: Clone> Grid<T> {
+    pub fn new(width: usize, height: usize, value: T) -> Self {
+        Grid::from_vec(width, vec![value; width * height])
+    }
+}
+
+impl Grid<char> {
+    pub fn parse(input: &str) -> Result<Self, ParseError> {
+        Grid::parse_with(input, Some)
+    }
+}
+
+impl<T> Index<Coord> for Grid<T> {
+    type Output = T;
+
+    fn index(&self, coord: Coord) -> &T {
+        self.get(coord)
+            .unwrap_or_else(|| panic!("{coord} lies outside the grid"))
+    }
+}
+
+impl<T> IndexMut<Coord> for Grid<T> {
+    fn index_mut(&mut self, coord: Coord) -> &mut T {
+        self.get_mut(coord)
+            .unwrap_or_else(|| panic!("{coord} lies outside the grid"))
+    }
+}
+
+impl<T: fmt::Display> fmt::Display for Grid<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (index, row) in self.rows().enumerate() {
+            if index > 0 {
+                writeln!(f)?;
+            }
+            for cell in row {
+                write!(f, "{cell}")?;
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const EXAMPLE: &str = "ab.
+.c#";
+
+    #[test]
+    fn test_parse() {
+        let grid = Grid::parse(EXAMPLE).unwrap();
+        assert_eq!((grid.width(), grid.height()), (3, 2));
+        assert_eq!(grid.get(Coord::new(1, 1)), Some(&'c'));
+        assert_eq!(grid.get(Coord::new(2, 0)), None);
+        assert_eq!(grid.get(Coord::new(0, -1)), None);
+        assert_eq!(grid.to_string(), EXAMPLE);
+
+        let err = Grid::parse_with(EXAMPLE, |c| c.is_alphabetic().then_some(c)).unwrap_err();
+        assert_eq!((err.line, err.column), (1, 3));
+        assert!(Grid::parse("ab\nc").is_err());
+        let err = Grid::parse("ab\naö").unwrap_err();
+        assert_eq!((err.line, err.column), (2, 2));
+    }
+
+    #[test]
+    fn test_set() {
+        let mut grid = Grid::new(2, 2, 0);
+        assert_eq!(grid.set(Coord::new(1, 0), 5), Some(0));
+        assert_eq!(grid.set(Coord::new(2, 0), 5), None);
+        grid[Coord::new(0, 1)] += 1;
+        assert_eq!(grid.values().copied().collect::<Vec<_>>(), [0, 1, 5, 0]);
+    }
+
+    #[test]
+    fn test_iteration() {
+        let grid = Grid::parse(EXAMPLE).unwrap();
+        assert_eq!(grid.coords().count(), 6);
+        assert_eq!(grid.find(&'c'), Some(Coord::new(1, 1)));
+        assert_eq!(grid.find_all(&'.').count(), 2);
+    }
+
+    #[test]
+    fn test_neighbours() {
+        let grid = Grid::new(3, 3, ());
+        assert_eq!(grid.neighbours4(Coord::new(1, 1)).count(), 4);
+        assert_eq!(
+            grid.neighbours4(Coord::new(0, 0)).collect::<Vec<_>>(),
+            [Coord::new(0, 1), Coord::new(1, 0)]
+        );
+    }
+
+    #[test]
+    fn test_coord() {
+        assert_eq!(Coord::UP.turn_right(), Coord::RIGHT);
+        assert_eq!(Coord::RIGHT.turn_left(), Coord::UP);
+        assert_eq!(Coord::new(1, 2) + Coord::DOWN * 3, Coord::new(4, 2));
+        assert_eq!(Coord::new(1, 2).manhattan(Coord::new(-1, 5)), 5);
+    }
+}
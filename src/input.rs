@@ -0,0 +1,113 @@
+use std::env;
+use std::fmt;
+use std::io::{self, Read};
+use std::path::{Path, PathBuf};
+
+/// Overrides the directory the puzzle inputs are looked up in.
+const INPUT_DIR_VAR: &str = "AOC_INPUT_DIR";
+const DEFAULT_INPUT_DIR: &str = "inputs";
+
+/// Where the input of a day is read from.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Source {
+    /// `day{NN}.txt` in the inputs directory.
+    InputDir,
+    File(PathBuf),
+    Stdin,
+}
+
+impl Source {
+    /// Interprets a `--input` argument, `-` stands for stdin.
+    pub fn from_arg(arg: &str) -> Self {
+        match arg {
+            "-" => Source::Stdin,
+            path => Source::File(PathBuf::from(path)),
+        }
+    }
+
+    pub fn read(&self, day: u32) -> Result<String, InputError> {
+        match self {
+            Source::InputDir => read_file(&path_in(
+                &dir_from_env(INPUT_DIR_VAR, DEFAULT_INPUT_DIR),
+                day,
+            )),
+            Source::File(path) => read_file(path),
+            Source::Stdin => {
+                let mut input = String::new();
+                io::stdin()
+                    .read_to_string(&mut input)
+                    .map_err(|source| InputError { path: None, source })?;
+                Ok(input)
+            }
+        }
+    }
+}
+
+#[derive(Debug)]
+pub struct InputError {
+    /// `None` when reading from stdin.
+    pub path: Option<PathBuf>,
+    pub source: io::Error,
+}
+
+impl fmt::Display for InputError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match &self.path {
+            Some(path) => write!(f, "could not read `{}`: {}", path.display(), self.source),
+            None => write!(f, "could not read stdin: {}", self.source),
+        }
+    }
+}
+
+impl std::error::Error for InputError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        Some(&self.source)
+    }
+}
+
+/// The directory named by the environment variable `var`, or `default` if it
+/// is not set.
+pub fn dir_from_env(var: &str, default: &str) -> PathBuf {
+    env::var_os(var)
+        .filter(|dir| !dir.is_empty())
+        .map_or_else(|| PathBuf::from(default), PathBuf::from)
+}
+
+/// The `dayNN.txt` file of `day` in `dir`.
+pub fn path_in(dir: &Path, day: u32) -> PathBuf {
+    dir.join(format!("day{day:02}.txt"))
+}
+
+fn read_file(path: &Path) -> Result<String, InputError> {
+    std::fs::read_to_string(path).map_err(|source| InputError {
+        path: Some(path.to_path_buf()),
+        source,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_from_arg() {
+        assert_eq!(Source::from_arg("-"), Source::Stdin);
+        assert_eq!(
+            Source::from_arg("alt/day05.txt"),
+            Source::File(PathBuf::from("alt/day05.txt"))
+        );
+        assert_eq!(
+            path_in(Path::new("inputs"), 7),
+            PathBuf::from("inputs/day07.txt")
+        );
+    }
+
+    #[test]
+    fn test_missing_file() {
+        let err = Source::from_arg("no/such/day05.txt").read(5).unwrap_err();
+        assert_eq!(err.path, Some(PathBuf::from("no/such/day05.txt")));
+        assert!(err
+            .to_string()
+            .starts_with("could not read `no/such/day05.txt`: "));
+    }
+}
@@ -1,18 +1,464 @@
-use std::io::Read;
+use std::fmt;
+use std::ops::Bound;
+use std::process::ExitCode;
 
+use answers::KnownAnswers;
+use bench::{Row, Timings};
+use cli::{Command, Format};
+use input::{InputError, Source};
+use parse::ParseError;
+use report::{DayReport, Outcome, PartReport};
+use solution::{Example, Part, PartResult, Solution};
+
+mod answers;
+mod bench;
+mod cli;
 mod day01;
+mod day02;
+mod day03;
+mod day04;
+mod day05;
+mod day06;
+mod day07;
+mod day08;
+mod day09;
+mod day10;
+mod day11;
+mod day12;
+mod day14;
+mod day15;
+mod day16;
+mod day17;
+mod day18;
+mod day19;
+mod day20;
+mod day21;
+mod day22;
+mod day23;
+mod day24;
+mod day25;
+mod grid;
+mod input;
+mod parse;
+mod report;
+mod search;
+mod solution;
+
+type RunFn = fn(&str, &[Part]) -> Result<Vec<PartResult>, ParseError>;
+type BenchFn = fn(&str, usize) -> Result<Timings, ParseError>;
+
+/// The type-erased entry points of a day's `Solution`.
+struct Day {
+    run: RunFn,
+    run_example: RunFn,
+    bench: BenchFn,
+    examples: &'static [Example],
+}
+
+impl Day {
+    fn of<S: Solution>() -> Self {
+        Day {
+            run: solution::run::<S>,
+            run_example: solution::run_example::<S>,
+            bench: bench::bench::<S>,
+            examples: S::EXAMPLES,
+        }
+    }
+}
+
+fn lookup(day: u32) -> Option<Day> {
+    let entry = match day {
+        1 => Day::of::<day01::Day01>(),
+        2 => Day::of::<day02::Day02>(),
+        3 => Day::of::<day03::Day03>(),
+        4 => Day::of::<day04::Day04>(),
+        5 => Day::of::<day05::Day05>(),
+        6 => Day::of::<day06::Day06>(),
+        7 => Day::of::<day07::Day07>(),
+        8 => Day::of::<day08::Day08>(),
+        9 => Day::of::<day09::Day09>(),
+        10 => Day::of::<day10::Day10>(),
+        11 => Day::of::<day11::Day11>(),
+        12 => Day::of::<day12::Day12>(),
+        14 => Day::of::<day14::Day14>(),
+        15 => Day::of::<day15::Day15>(),
+        16 => Day::of::<day16::Day16>(),
+        17 => Day::of::<day17::Day17>(),
+        18 => Day::of::<day18::Day18>(),
+        19 => Day::of::<day19::Day19>(),
+        20 => Day::of::<day20::Day20>(),
+        21 => Day::of::<day21::Day21>(),
+        22 => Day::of::<day22::Day22>(),
+        23 => Day::of::<day23::Day23>(),
+        24 => Day::of::<day24::Day24>(),
+        25 => Day::of::<day25::Day25>(),
+        _ => return None,
+    };
+
+    Some(entry)
+}
+
+#[derive(Debug)]
+enum RunError {
+    Input(InputError),
+    Parse(ParseError),
+}
+
+impl fmt::Display for RunError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            RunError::Input(err) => write!(f, "{err}"),
+            RunError::Parse(err) => write!(f, "could not parse the input of {err}"),
+        }
+    }
+}
+
+fn read_input(day: u32, source: &Source) -> Result<String, RunError> {
+    // parsers only deal with `\n`, so inputs saved with CRLF line endings work as well
+    Ok(source
+        .read(day)
+        .map_err(RunError::Input)?
+        .replace("\r\n", "\n"))
+}
 
-fn run(day: u32, solve_fn: fn(&str)) {
-    let mut input_file =
-        std::fs::File::open(format!("inputs/day{day:02}.txt")).expect("Could not open file");
-    let mut input = String::new();
-    input_file
-        .read_to_string(&mut input)
-        .expect("Reading file failed!");
+/// Runs the given parts of a day; with `verify` the answers are compared with
+/// the known ones.
+fn run(day: u32, parts: &[Part], source: &Source, verify: bool) -> DayReport {
+    let outcome = match lookup(day) {
+        Some(entry) => solve_input(day, &entry, parts, source, verify)
+            .map_or_else(|err| Outcome::Error(err.to_string()), Outcome::Solved),
+        None => Outcome::NotImplemented,
+    };
 
-    solve_fn(input.trim());
+    DayReport {
+        day,
+        example: None,
+        parts: parts.to_vec(),
+        outcome,
+    }
 }
 
-fn main() {
-    run(1, day01::solve);
+/// Runs the given parts of a day on each of its examples and checks the
+/// answers given for them, skipping the parts an example has no answer for.
+fn run_examples(day: u32, parts: &[Part]) -> Vec<DayReport> {
+    let report = |example, parts: &[Part], outcome| DayReport {
+        day,
+        example,
+        parts: parts.to_vec(),
+        outcome,
+    };
+
+    let Some(entry) = lookup(day) else {
+        return vec![report(None, parts, Outcome::NotImplemented)];
+    };
+    if entry.examples.is_empty() {
+        return vec![report(None, parts, Outcome::NoExample)];
+    }
+
+    entry
+        .examples
+        .iter()
+        .enumerate()
+        .map(|(index, example)| {
+            let known = KnownAnswers::of_example(example);
+            let parts: Vec<_> = parts
+                .iter()
+                .copied()
+                .filter(|&part| known.get(part).is_some())
+                .collect();
+            let outcome = solve(day, entry.run_example, &parts, example.input, Some(&known))
+                .map_or_else(|err| Outcome::Error(err.to_string()), Outcome::Solved);
+            report(Some(index + 1), &parts, outcome)
+        })
+        .collect()
+}
+
+fn solve_input(
+    day: u32,
+    entry: &Day,
+    parts: &[Part],
+    source: &Source,
+    verify: bool,
+) -> Result<Vec<PartReport>, RunError> {
+    let input = read_input(day, source)?;
+    let known = if verify {
+        Some(KnownAnswers::load(day).map_err(RunError::Input)?)
+    } else {
+        None
+    };
+
+    solve(day, entry.run, parts, input.trim(), known.as_ref())
+}
+
+fn solve(
+    day: u32,
+    run: RunFn,
+    parts: &[Part],
+    input: &str,
+    known: Option<&KnownAnswers>,
+) -> Result<Vec<PartReport>, RunError> {
+    let results = run(input, parts).map_err(|err| RunError::Parse(err.in_day(day)))?;
+
+    Ok(results
+        .into_iter()
+        .map(|result| PartReport {
+            part: result.part,
+            verdict: known
+                .zip(result.answer.as_ref().ok().and_then(Option::as_ref))
+                .map(|(known, answer)| known.verify(result.part, answer)),
+            answer: result.answer,
+            duration: result.duration,
+        })
+        .collect())
+}
+
+/// Times a day and prints its rows of the benchmark table.
+fn bench(day: u32, iterations: usize) -> Result<(), RunError> {
+    let Some(entry) = lookup(day) else {
+        return Ok(());
+    };
+
+    let input = read_input(day, &Source::InputDir)?;
+    let timings =
+        (entry.bench)(input.trim(), iterations).map_err(|err| RunError::Parse(err.in_day(day)))?;
+
+    let parts = timings
+        .parts
+        .into_iter()
+        .map(|(part, stats)| (format!("part {}", part.number()), stats));
+    for (step, stats) in [("parse".to_string(), Some(timings.parse))]
+        .into_iter()
+        .chain(parts)
+    {
+        println!("{}", Row { day, step, stats });
+    }
+
+    Ok(())
+}
+
+fn parse_day17(source: &Source) -> Result<day17::Computer, RunError> {
+    let input = read_input(17, source)?;
+    day17::Day17::parse(input.trim()).map_err(|err| RunError::Parse(err.in_day(17)))
+}
+
+fn disassemble(source: &Source) -> Result<(), RunError> {
+    let computer = parse_day17(source)?;
+    println!("{}", day17::disassemble(&computer.program));
+
+    Ok(())
+}
+
+/// Prints the trace of day 17's program, returns whether it halted.
+fn trace(source: &Source, steps: usize) -> Result<bool, RunError> {
+    let mut computer = parse_day17(source)?;
+    let trace = computer.trace(steps);
+    println!("{trace}");
+
+    Ok(trace.end == day17::TraceEnd::Halted)
+}
+
+/// Draws the path of day 6's guard, with `loops` once per obstruction that
+/// makes her walk in a loop.
+fn guard(source: &Source, loops: bool) -> Result<(), RunError> {
+    let input = read_input(6, source)?;
+    let (state, grid) =
+        day06::Day06::parse(input.trim()).map_err(|err| RunError::Parse(err.in_day(6)))?;
+
+    println!(
+        "{}",
+        day06::render(&grid, &day06::walk(state.clone(), &grid), None)
+    );
+    if loops {
+        let obstructions = day06::loop_obstructions(state.clone(), &grid);
+        for obstruction in obstructions.unwrap_or_default() {
+            let mut grid = grid.clone();
+            grid.set(obstruction, '#');
+            let path = day06::walk(state.clone(), &grid);
+            println!("\n{}", day06::render(&grid, &path, Some(obstruction)));
+        }
+    }
+
+    Ok(())
+}
+
+/// Prints the equations of day 7 that the operators solve and their total.
+fn equations(source: &Source, operators: &[&dyn day07::Operator]) -> Result<(), RunError> {
+    let input = read_input(7, source)?;
+    let equations =
+        day07::Day07::parse(input.trim()).map_err(|err| RunError::Parse(err.in_day(7)))?;
+
+    for equation in &equations {
+        if let Some(chosen) = day07::solve(equation, operators) {
+            println!("{}", day07::format_solution(equation, &chosen));
+        }
+    }
+    println!("total: {}", day07::total(&equations, operators));
+
+    Ok(())
+}
+
+/// Draws the antinodes of day 8 for the harmonics and counts them per
+/// frequency.
+fn antinodes(source: &Source, harmonics: (Bound<i64>, Bound<i64>)) -> Result<(), RunError> {
+    let input = read_input(8, source)?;
+    let city = day08::Day08::parse(input.trim()).map_err(|err| RunError::Parse(err.in_day(8)))?;
+
+    let antinodes = day08::antinodes(&city, harmonics);
+    println!("{}\n", day08::render(&city, &antinodes));
+    for (frequency, antinodes) in day08::antinodes_by_frequency(&city, harmonics) {
+        println!("{frequency}: {}", antinodes.len());
+    }
+    println!("total: {}", antinodes.len());
+
+    Ok(())
+}
+
+/// Compacts the disk of day 9 and prints the result and the moves.
+fn compact(source: &Source, whole_files: bool, list_moves: bool) -> Result<(), RunError> {
+    let input = read_input(9, source)?;
+    let disk = day09::Day09::parse(input.trim()).map_err(|err| RunError::Parse(err.in_day(9)))?;
+
+    let (compacted, moves) = if whole_files {
+        day09::move_files(&disk)
+    } else {
+        day09::move_blocks(&disk)
+    };
+    println!("{}", compacted.to_map());
+    if let Some(blocks) = compacted.render() {
+        println!("{blocks}");
+    }
+    if list_moves {
+        for step in &moves {
+            println!("{step}");
+        }
+    }
+    println!("{}", day09::summarize(&moves));
+
+    Ok(())
+}
+
+fn main() -> ExitCode {
+    match cli::parse_args(std::env::args().skip(1)) {
+        Ok(Command::Run {
+            days,
+            part,
+            input,
+            verify,
+            example,
+            format,
+        }) => {
+            let parts = match part {
+                Some(part) => vec![part],
+                None => vec![Part::One, Part::Two],
+            };
+
+            let mut reports = Vec::new();
+            for day in days {
+                let day_reports = if example {
+                    run_examples(day, &parts)
+                } else {
+                    vec![run(day, &parts, &input, verify)]
+                };
+                for report in day_reports {
+                    if format == Format::Text {
+                        report.print_text();
+                    }
+                    reports.push(report);
+                }
+            }
+            if format == Format::Json {
+                report::print_json(&reports);
+            }
+
+            if reports.iter().all(DayReport::is_ok) {
+                ExitCode::SUCCESS
+            } else {
+                ExitCode::FAILURE
+            }
+        }
+        Ok(Command::Bench { days, iterations }) => {
+            println!("{}", Row::HEADER);
+
+            let mut exit_code = ExitCode::SUCCESS;
+            for day in days {
+                if let Err(err) = bench(day, iterations) {
+                    eprintln!("error: {err}");
+                    exit_code = ExitCode::FAILURE;
+                }
+            }
+
+            exit_code
+        }
+        Ok(Command::Disassemble { input }) => match disassemble(&input) {
+            Ok(()) => ExitCode::SUCCESS,
+            Err(err) => {
+                eprintln!("error: {err}");
+                ExitCode::FAILURE
+            }
+        },
+        Ok(Command::Trace { input, steps }) => match trace(&input, steps) {
+            Ok(true) => ExitCode::SUCCESS,
+            Ok(false) => ExitCode::FAILURE,
+            Err(err) => {
+                eprintln!("error: {err}");
+                ExitCode::FAILURE
+            }
+        },
+        Ok(Command::Guard { input, loops }) => match guard(&input, loops) {
+            Ok(()) => ExitCode::SUCCESS,
+            Err(err) => {
+                eprintln!("error: {err}");
+                ExitCode::FAILURE
+            }
+        },
+        Ok(Command::Equations { input, operators }) => match equations(&input, &operators) {
+            Ok(()) => ExitCode::SUCCESS,
+            Err(err) => {
+                eprintln!("error: {err}");
+                ExitCode::FAILURE
+            }
+        },
+        Ok(Command::Antinodes { input, harmonics }) => match antinodes(&input, harmonics) {
+            Ok(()) => ExitCode::SUCCESS,
+            Err(err) => {
+                eprintln!("error: {err}");
+                ExitCode::FAILURE
+            }
+        },
+        Ok(Command::Compact {
+            input,
+            whole_files,
+            moves,
+        }) => match compact(&input, whole_files, moves) {
+            Ok(()) => ExitCode::SUCCESS,
+            Err(err) => {
+                eprintln!("error: {err}");
+                ExitCode::FAILURE
+            }
+        },
+        Ok(Command::Help) => {
+            println!("{}", cli::USAGE);
+            ExitCode::SUCCESS
+        }
+        Err(err) => {
+            eprintln!("error: {err}\n\n{}", cli::USAGE);
+            ExitCode::from(2)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // the answers are checked by the tests of the days, running all of them
+    // again would take too long
+    #[test]
+    fn test_examples_parse() {
+        for day in 1..=25 {
+            for report in run_examples(day, &[]) {
+                assert!(report.is_ok(), "{report:?}");
+            }
+        }
+    }
 }
@@ -0,0 +1,218 @@
+use std::fmt;
+use std::str::FromStr;
+
+/// Why and where a puzzle input could not be parsed.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseError {
+    pub day: Option<u32>,
+    pub line: usize,
+    pub column: usize,
+    pub text: String,
+    pub message: String,
+}
+
+impl ParseError {
+    /// Points at `text`, which has to be a slice of `input`; line and column
+    /// are derived from where it lies within `input` (both start at 1).
+    pub fn new(input: &str, text: &str, message: impl Into<String>) -> Self {
+        let offset = (text.as_ptr() as usize)
+            .checked_sub(input.as_ptr() as usize)
+            .filter(|&offset| offset <= input.len())
+            .unwrap_or(input.len());
+        let before = input.get(..offset).unwrap_or(input);
+        let line_start = before.rfind('\n').map_or(0, |index| index + 1);
+
+        ParseError {
+            day: None,
+            line: before.matches('\n').count() + 1,
+            column: before[line_start..].chars().count() + 1,
+            text: text.to_string(),
+            message: message.into(),
+        }
+    }
+
+    /// Points just behind `text`, e.g. when a line ends too early.
+    pub fn after(input: &str, text: &str, message: impl Into<String>) -> Self {
+        ParseError::new(input, &text[text.len()..], message)
+    }
+
+    /// Moves an error found in a line that was parsed on its own, e.g. when
+    /// reading the input line by line, to line `line` of the whole input.
+    pub fn on_line(self, line: usize) -> Self {
+        ParseError {
+            line: line + self.line - 1,
+            ..self
+        }
+    }
+
+    pub fn in_day(self, day: u32) -> Self {
+        ParseError {
+            day: Some(day),
+            ..self
+        }
+    }
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if let Some(day) = self.day {
+            write!(f, "day {day:02}, ")?;
+        }
+        write!(
+            f,
+            "line {}, column {}: {}",
+            self.line, self.column, self.message
+        )?;
+        if !self.text.is_empty() {
+            write!(f, ", found `{}`", self.text)?;
+        }
+        Ok(())
+    }
+}
+
+impl std::error::Error for ParseError {}
+
+pub fn number<T: FromStr>(input: &str, text: &str) -> Result<T, ParseError> {
+    text.parse()
+        .map_err(|_| ParseError::new(input, text, "expected a number"))
+}
+
+pub fn split_once<'a>(
+    input: &str,
+    text: &'a str,
+    delimiter: &str,
+) -> Result<(&'a str, &'a str), ParseError> {
+    text.split_once(delimiter).ok_or_else(|| {
+        ParseError::new(
+            input,
+            text,
+            format!("expected `{}`", delimiter.escape_debug()),
+        )
+    })
+}
+
+pub fn strip_prefix<'a>(input: &str, text: &'a str, prefix: &str) -> Result<&'a str, ParseError> {
+    text.strip_prefix(prefix)
+        .ok_or_else(|| ParseError::new(input, text, format!("expected `{prefix}`")))
+}
+
+/// Checks that all lines of a grid are ASCII and have the same, non-zero
+/// width.
+pub fn grid_lines(input: &str) -> Result<Vec<&str>, ParseError> {
+    if let Some((index, c)) = input.char_indices().find(|(_, c)| !c.is_ascii()) {
+        let text = &input[index..index + c.len_utf8()];
+        return Err(ParseError::new(input, text, "unexpected character"));
+    }
+
+    let lines: Vec<&str> = input.lines().collect();
+    let width = lines.first().map_or(0, |line| line.len());
+    if width == 0 {
+        return Err(ParseError::new(input, input, "expected a grid"));
+    }
+
+    match lines.iter().find(|line| line.len() != width) {
+        Some(line) => Err(ParseError::new(
+            input,
+            line,
+            format!("expected a row of width {width}"),
+        )),
+        None => Ok(lines),
+    }
+}
+
+/// Fails on the first character of `text` (a slice of `input`) that is
+/// neither in `allowed` nor a line break.
+pub fn only_chars(input: &str, text: &str, allowed: &str) -> Result<(), ParseError> {
+    match text
+        .char_indices()
+        .find(|&(_, c)| c != '\n' && !allowed.contains(c))
+    {
+        Some((index, c)) => Err(ParseError::new(
+            input,
+            &text[index..index + c.len_utf8()],
+            "unexpected character",
+        )),
+        None => Ok(()),
+    }
+}
+
+/// Fails unless `c` occurs exactly once, pointing at the second occurrence
+/// or at the end of `input` when it is missing.
+pub fn exactly_one(input: &str, c: char) -> Result<(), ParseError> {
+    let message = format!("expected exactly one `{c}`");
+    match input.match_indices(c).nth(1) {
+        Some((index, text)) => Err(ParseError::new(
+            input,
+            &input[index..index + text.len()],
+            message,
+        )),
+        None if !input.contains(c) => Err(ParseError::after(input, input, message)),
+        None => Ok(()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const INPUT: &str = "1 2\n3 x4\n";
+
+    #[test]
+    fn test_position() {
+        let err = ParseError::new(INPUT, &INPUT[6..8], "expected a number");
+        assert_eq!((err.line, err.column), (2, 3));
+        assert_eq!(err.text, "x4");
+        assert_eq!(
+            err.in_day(1).to_string(),
+            "day 01, line 2, column 3: expected a number, found `x4`"
+        );
+    }
+
+    #[test]
+    fn test_after() {
+        let err = ParseError::after(INPUT, &INPUT[0..3], "expected `|`");
+        assert_eq!((err.line, err.column), (1, 4));
+        assert_eq!(err.to_string(), "line 1, column 4: expected `|`");
+        assert_eq!(err.on_line(7).line, 7);
+    }
+
+    #[test]
+    fn test_helpers() {
+        let line = INPUT.lines().nth(1).unwrap();
+        let (left, right) = split_once(INPUT, line, " ").unwrap();
+        assert_eq!(number::<u32>(INPUT, left), Ok(3));
+        assert_eq!(number::<u32>(INPUT, right).unwrap_err().column, 3);
+        assert_eq!(split_once(INPUT, line, "|").unwrap_err().line, 2);
+        assert_eq!(strip_prefix(INPUT, line, "3 "), Ok("x4"));
+        assert_eq!(strip_prefix(INPUT, line, "x").unwrap_err().column, 1);
+    }
+
+    #[test]
+    fn test_grid_lines() {
+        assert_eq!(grid_lines("ab\ncd"), Ok(vec!["ab", "cd"]));
+        let err = grid_lines("ab\nc\nde").unwrap_err();
+        assert_eq!((err.line, err.column, err.text.as_str()), (2, 1, "c"));
+        assert!(grid_lines("").is_err());
+        let err = grid_lines("ö\nab").unwrap_err();
+        assert_eq!((err.line, err.column, err.text.as_str()), (1, 1, "ö"));
+    }
+
+    #[test]
+    fn test_only_chars() {
+        assert_eq!(only_chars("#.\n.#", "#.\n.#", ".#"), Ok(()));
+        let input = "#.\n.ö\n\n#x";
+        let err = only_chars(input, &input[..6], ".#").unwrap_err();
+        assert_eq!((err.line, err.column, err.text.as_str()), (2, 2, "ö"));
+        let err = only_chars(input, &input[8..], ".#").unwrap_err();
+        assert_eq!((err.line, err.column, err.text.as_str()), (4, 2, "x"));
+    }
+
+    #[test]
+    fn test_exactly_one() {
+        assert_eq!(exactly_one("#S\n.E", 'S'), Ok(()));
+        let err = exactly_one("#S\nS.", 'S').unwrap_err();
+        assert_eq!((err.line, err.column, err.text.as_str()), (2, 1, "S"));
+        let err = exactly_one("#.\n..", 'S').unwrap_err();
+        assert_eq!((err.line, err.column), (2, 3));
+    }
+}
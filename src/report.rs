@@ -0,0 +1,287 @@
+use std::fmt::Write;
+use std::time::Duration;
+
+use crate::answers::Verdict;
+use crate::solution::{Answer, Part};
+
+/// What running one part of a day produced.
+#[derive(Debug, PartialEq, Eq)]
+pub struct PartReport {
+    pub part: Part,
+    /// Why the part could not be solved, if it failed.
+    pub answer: Result<Option<Answer>, String>,
+    pub duration: Duration,
+    /// Only set when the answers are verified.
+    pub verdict: Option<Verdict>,
+}
+
+impl PartReport {
+    pub fn status(&self) -> &'static str {
+        match (&self.answer, &self.verdict) {
+            (Err(_), _) => "error",
+            (Ok(None), _) => "not_implemented",
+            (Ok(Some(_)), None) => "solved",
+            (Ok(Some(_)), Some(Verdict::Pass)) => "pass",
+            (Ok(Some(_)), Some(Verdict::Fail { .. })) => "fail",
+            (Ok(Some(_)), Some(Verdict::Unknown)) => "unknown",
+        }
+    }
+}
+
+#[derive(Debug, PartialEq, Eq)]
+pub enum Outcome {
+    Solved(Vec<PartReport>),
+    NotImplemented,
+    /// Running the examples of a day that has none.
+    NoExample,
+    /// The input could not be read or parsed.
+    Error(String),
+}
+
+#[derive(Debug, PartialEq, Eq)]
+pub struct DayReport {
+    pub day: u32,
+    /// The number of the example that was run instead of the puzzle input.
+    pub example: Option<usize>,
+    pub parts: Vec<Part>,
+    pub outcome: Outcome,
+}
+
+impl DayReport {
+    /// Whether nothing went wrong: the input was parsed, no part failed and
+    /// no answer differs from the known one.
+    pub fn is_ok(&self) -> bool {
+        match &self.outcome {
+            Outcome::Solved(reports) => reports.iter().all(|report| {
+                report.answer.is_ok() && !matches!(report.verdict, Some(Verdict::Fail { .. }))
+            }),
+            Outcome::NotImplemented | Outcome::NoExample => true,
+            Outcome::Error(_) => false,
+        }
+    }
+
+    pub fn print_text(&self) {
+        match self.example {
+            Some(example) => println!("Day {:02} (example {example})", self.day),
+            None => println!("Day {:02}", self.day),
+        }
+
+        match &self.outcome {
+            Outcome::Solved(reports) => {
+                for report in reports {
+                    let number = report.part.number();
+                    match (&report.answer, &report.verdict) {
+                        (Ok(Some(answer)), Some(verdict)) => {
+                            println!("  part {number}: {answer} ({verdict})")
+                        }
+                        (Ok(Some(answer)), None) => println!("  part {number}: {answer}"),
+                        (Ok(None), _) => println!("  part {number}: not implemented"),
+                        (Err(message), _) => eprintln!("  part {number}: error: {message}"),
+                    }
+                }
+            }
+            Outcome::NotImplemented => println!("  not implemented"),
+            Outcome::NoExample => println!("  no example"),
+            Outcome::Error(message) => eprintln!("error: {message}"),
+        }
+    }
+
+    /// One JSON object per part, e.g.
+    /// `{"day": 1, "part": 2, "answer": 31, "duration_ms": 0.012, "status": "solved"}`.
+    pub fn json_objects(&self) -> Vec<String> {
+        let null = || "null".to_string();
+
+        match &self.outcome {
+            Outcome::Solved(reports) => reports
+                .iter()
+                .map(|report| {
+                    let answer = match &report.answer {
+                        Ok(Some(Answer::Number(number))) => number.to_string(),
+                        Ok(Some(Answer::Text(text))) => json_string(text),
+                        Ok(None) | Err(_) => null(),
+                    };
+                    let duration = report.duration.as_secs_f64() * 1000.0;
+                    let mut fields = vec![
+                        ("answer", answer),
+                        ("duration_ms", format!("{duration:.3}")),
+                        ("status", json_string(report.status())),
+                    ];
+                    if let Some(Verdict::Fail { expected }) = &report.verdict {
+                        fields.push(("expected", json_string(expected)));
+                    }
+                    if let Err(message) = &report.answer {
+                        fields.push(("error", json_string(message)));
+                    }
+                    self.json_object(report.part, fields)
+                })
+                .collect(),
+            Outcome::NotImplemented | Outcome::NoExample => self
+                .parts
+                .iter()
+                .map(|&part| {
+                    let status = match self.outcome {
+                        Outcome::NoExample => "no_example",
+                        _ => "not_implemented",
+                    };
+                    let fields = vec![
+                        ("answer", null()),
+                        ("duration_ms", null()),
+                        ("status", json_string(status)),
+                    ];
+                    self.json_object(part, fields)
+                })
+                .collect(),
+            Outcome::Error(message) => self
+                .parts
+                .iter()
+                .map(|&part| {
+                    let fields = vec![
+                        ("answer", null()),
+                        ("duration_ms", null()),
+                        ("status", json_string("error")),
+                        ("error", json_string(message)),
+                    ];
+                    self.json_object(part, fields)
+                })
+                .collect(),
+        }
+    }
+
+    /// `fields` hold values that are already encoded as JSON.
+    fn json_object(&self, part: Part, fields: Vec<(&str, String)>) -> String {
+        let mut object = format!("{{\"day\": {}", self.day);
+        if let Some(example) = self.example {
+            write!(object, ", \"example\": {example}").unwrap();
+        }
+        write!(object, ", \"part\": {}", part.number()).unwrap();
+        for (key, value) in fields {
+            write!(object, ", \"{key}\": {value}").unwrap();
+        }
+        object.push('}');
+
+        object
+    }
+}
+
+/// Prints the reports of all days as a single JSON array.
+pub fn print_json(reports: &[DayReport]) {
+    let objects: Vec<_> = reports.iter().flat_map(DayReport::json_objects).collect();
+
+    if objects.is_empty() {
+        println!("[]");
+    } else {
+        println!("[\n  {}\n]", objects.join(",\n  "));
+    }
+}
+
+fn json_string(text: &str) -> String {
+    let mut quoted = String::with_capacity(text.len() + 2);
+    quoted.push('"');
+    for c in text.chars() {
+        match c {
+            '"' => quoted.push_str("\\\""),
+            '\\' => quoted.push_str("\\\\"),
+            '\n' => quoted.push_str("\\n"),
+            '\r' => quoted.push_str("\\r"),
+            '\t' => quoted.push_str("\\t"),
+            c if c.is_control() => write!(quoted, "\\u{:04x}", c as u32).unwrap(),
+            c => quoted.push(c),
+        }
+    }
+    quoted.push('"');
+
+    quoted
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_json_string() {
+        assert_eq!(json_string("co,de"), r#""co,de""#);
+        assert_eq!(
+            json_string("could not read `a\"b`\n\u{1}"),
+            r#""could not read `a\"b`\n\u0001""#
+        );
+    }
+
+    #[test]
+    fn test_json_objects() {
+        let report = DayReport {
+            day: 17,
+            example: None,
+            parts: vec![Part::One, Part::Two],
+            outcome: Outcome::Solved(vec![
+                PartReport {
+                    part: Part::One,
+                    answer: Ok(Some(Answer::Text("4,6,3".to_string()))),
+                    duration: Duration::from_micros(1500),
+                    verdict: Some(Verdict::Fail {
+                        expected: "4,6,4".to_string(),
+                    }),
+                },
+                PartReport {
+                    part: Part::Two,
+                    answer: Ok(None),
+                    duration: Duration::ZERO,
+                    verdict: None,
+                },
+            ]),
+        };
+        assert!(!report.is_ok());
+        assert_eq!(
+            report.json_objects(),
+            [
+                r#"{"day": 17, "part": 1, "answer": "4,6,3", "duration_ms": 1.500, "status": "fail", "expected": "4,6,4"}"#,
+                r#"{"day": 17, "part": 2, "answer": null, "duration_ms": 0.000, "status": "not_implemented"}"#,
+            ]
+        );
+
+        let report = DayReport {
+            day: 17,
+            example: None,
+            parts: vec![Part::One],
+            outcome: Outcome::Solved(vec![PartReport {
+                part: Part::One,
+                answer: Err("the instruction at 2 has the reserved operand 7".to_string()),
+                duration: Duration::from_micros(20),
+                verdict: None,
+            }]),
+        };
+        assert!(!report.is_ok());
+        assert_eq!(
+            report.json_objects(),
+            [
+                r#"{"day": 17, "part": 1, "answer": null, "duration_ms": 0.020, "status": "error", "error": "the instruction at 2 has the reserved operand 7"}"#
+            ]
+        );
+
+        let report = DayReport {
+            day: 13,
+            example: None,
+            parts: vec![Part::Two],
+            outcome: Outcome::NotImplemented,
+        };
+        assert!(report.is_ok());
+        assert_eq!(
+            report.json_objects(),
+            [
+                r#"{"day": 13, "part": 2, "answer": null, "duration_ms": null, "status": "not_implemented"}"#
+            ]
+        );
+
+        let report = DayReport {
+            day: 14,
+            example: Some(1),
+            parts: vec![Part::One],
+            outcome: Outcome::NoExample,
+        };
+        assert_eq!(
+            report.json_objects(),
+            [
+                r#"{"day": 14, "example": 1, "part": 1, "answer": null, "duration_ms": null, "status": "no_example"}"#
+            ]
+        );
+    }
+}
@@ -0,0 +1,256 @@
+use std::cmp::Reverse;
+use std::collections::{BinaryHeap, HashMap, HashSet, VecDeque};
+use std::hash::Hash;
+
+/// What a search found out about the states it reached.
+///
+/// Besides the distance of every reached state it remembers *all* the
+/// predecessors a state can be reached from at that distance, so every optimal
+/// path can be recovered, not just one of them.
+#[derive(Debug, Clone)]
+pub struct SearchResult<S> {
+    distances: HashMap<S, u64>,
+    predecessors: HashMap<S, Vec<S>>,
+    goals: Vec<S>,
+}
+
+impl<S: Clone + Eq + Hash> SearchResult<S> {
+    fn new() -> Self {
+        SearchResult {
+            distances: HashMap::new(),
+            predecessors: HashMap::new(),
+            goals: Vec::new(),
+        }
+    }
+
+    /// Records that `state` can be reached from `from` at `distance`;
+    /// returns whether this is the best distance so far.
+    fn relax(&mut self, from: &S, state: S, distance: u64) -> bool {
+        match self.distances.get(&state) {
+            Some(&known) if known < distance => false,
+            Some(&known) if known == distance => {
+                self.predecessors
+                    .entry(state)
+                    .or_default()
+                    .push(from.clone());
+                false
+            }
+            _ => {
+                self.distances.insert(state.clone(), distance);
+                self.predecessors.insert(state, vec![from.clone()]);
+                true
+            }
+        }
+    }
+
+    pub fn distance(&self, state: &S) -> Option<u64> {
+        self.distances.get(state).copied()
+    }
+
+    pub fn distances(&self) -> &HashMap<S, u64> {
+        &self.distances
+    }
+
+    /// The goal states reached at the smallest distance, empty if none was.
+    pub fn goals(&self) -> &[S] {
+        &self.goals
+    }
+
+    pub fn goal_distance(&self) -> Option<u64> {
+        self.goals.first().and_then(|goal| self.distance(goal))
+    }
+
+    /// All states `state` is reached from on an optimal path.
+    pub fn predecessors(&self, state: &S) -> &[S] {
+        self.predecessors.get(state).map_or(&[], Vec::as_slice)
+    }
+
+    /// Every state that lies on at least one optimal path to one of `ends`.
+    pub fn states_on_optimal_paths<'a>(&self, ends: impl IntoIterator<Item = &'a S>) -> HashSet<S>
+    where
+        S: 'a,
+    {
+        let mut todo: Vec<S> = ends
+            .into_iter()
+            .filter(|end| self.distances.contains_key(end))
+            .cloned()
+            .collect();
+        let mut seen: HashSet<S> = todo.iter().cloned().collect();
+
+        while let Some(state) = todo.pop() {
+            for previous in self.predecessors(&state) {
+                if seen.insert(previous.clone()) {
+                    todo.push(previous.clone());
+                }
+            }
+        }
+
+        seen
+    }
+}
+
+/// Breadth-first search where every step costs 1.
+///
+/// Stops once all goal states at the smallest distance are found; with a goal
+/// that is never reached the whole reachable graph is explored.
+pub fn bfs<S, I>(
+    starts: impl IntoIterator<Item = S>,
+    mut neighbours: impl FnMut(&S) -> I,
+    mut is_goal: impl FnMut(&S) -> bool,
+) -> SearchResult<S>
+where
+    S: Clone + Eq + Hash,
+    I: IntoIterator<Item = S>,
+{
+    let mut result = SearchResult::new();
+    let mut queue = VecDeque::new();
+
+    for start in starts {
+        if !result.distances.contains_key(&start) {
+            result.distances.insert(start.clone(), 0);
+            queue.push_back((start, 0));
+        }
+    }
+
+    while let Some((state, distance)) = queue.pop_front() {
+        if result.goal_distance().is_some_and(|best| distance > best) {
+            break;
+        }
+        if is_goal(&state) {
+            result.goals.push(state);
+            continue;
+        }
+
+        for next in neighbours(&state) {
+            if result.relax(&state, next.clone(), distance + 1) {
+                queue.push_back((next, distance + 1));
+            }
+        }
+    }
+
+    result
+}
+
+/// Dijkstra's algorithm over an implicit graph, `successors` yields each next
+/// state together with the cost of the step.
+pub fn dijkstra<S, I>(
+    starts: impl IntoIterator<Item = S>,
+    successors: impl FnMut(&S) -> I,
+    is_goal: impl FnMut(&S) -> bool,
+) -> SearchResult<S>
+where
+    S: Clone + Eq + Hash + Ord,
+    I: IntoIterator<Item = (S, u64)>,
+{
+    astar(starts, successors, |_| 0, is_goal)
+}
+
+/// A* search, `heuristic` must never overestimate the remaining cost to a
+/// goal (and be consistent) for the distances to be optimal.
+pub fn astar<S, I>(
+    starts: impl IntoIterator<Item = S>,
+    mut successors: impl FnMut(&S) -> I,
+    mut heuristic: impl FnMut(&S) -> u64,
+    mut is_goal: impl FnMut(&S) -> bool,
+) -> SearchResult<S>
+where
+    S: Clone + Eq + Hash + Ord,
+    I: IntoIterator<Item = (S, u64)>,
+{
+    let mut result = SearchResult::new();
+    let mut heap = BinaryHeap::new();
+
+    for start in starts {
+        if !result.distances.contains_key(&start) {
+            result.distances.insert(start.clone(), 0);
+            heap.push(Reverse((heuristic(&start), 0, start)));
+        }
+    }
+
+    while let Some(Reverse((estimate, distance, state))) = heap.pop() {
+        if result.goal_distance().is_some_and(|best| estimate > best) {
+            break;
+        }
+        if result.distances[&state] < distance {
+            continue; // a shorter way to this state was found in the meantime
+        }
+        if is_goal(&state) {
+            result.goals.push(state);
+            continue;
+        }
+
+        for (next, cost) in successors(&state) {
+            let next_distance = distance + cost;
+            if result.relax(&state, next.clone(), next_distance) {
+                let estimate = next_distance + heuristic(&next);
+                heap.push(Reverse((estimate, next_distance, next)));
+            }
+        }
+    }
+
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // 0 -> 1 -> 3 and 0 -> 2 -> 3 are equally short, 0 -> 4 -> 3 is not
+    fn graph(node: &u32) -> Vec<(u32, u64)> {
+        match node {
+            0 => vec![(1, 1), (2, 2), (4, 1)],
+            1 => vec![(3, 2)],
+            2 => vec![(3, 1)],
+            4 => vec![(3, 5)],
+            _ => vec![],
+        }
+    }
+
+    #[test]
+    fn test_dijkstra() {
+        let result = dijkstra([0], graph, |&node| node == 3);
+        assert_eq!(result.goals(), [3]);
+        assert_eq!(result.goal_distance(), Some(3));
+        assert_eq!(result.predecessors(&3), [1, 2]);
+        assert_eq!(
+            result.states_on_optimal_paths(result.goals()),
+            HashSet::from([0, 1, 2, 3])
+        );
+    }
+
+    #[test]
+    fn test_distance_map() {
+        let result = dijkstra([0], graph, |_| false);
+        assert!(result.goals().is_empty());
+        assert_eq!(result.distances().len(), 5);
+        assert_eq!(result.distance(&4), Some(1));
+        assert_eq!(result.distance(&5), None);
+    }
+
+    #[test]
+    fn test_astar() {
+        // walking on a line towards 10, the heuristic is the exact distance
+        let result = astar(
+            [0i64],
+            |&x| [(x - 1, 1), (x + 1, 1)],
+            |&x| x.abs_diff(10),
+            |&x| x == 10,
+        );
+        assert_eq!(result.goal_distance(), Some(10));
+        assert!(result.distance(&-1).is_some_and(|distance| distance == 1));
+        assert_eq!(result.distance(&-2), None);
+    }
+
+    #[test]
+    fn test_bfs() {
+        let neighbours = |node: &u32| graph(node).into_iter().map(|(next, _)| next);
+        let result = bfs([0], neighbours, |&node| node == 3);
+        assert_eq!(result.goal_distance(), Some(2));
+        assert_eq!(result.predecessors(&3), [1, 2, 4]);
+        assert_eq!(result.states_on_optimal_paths([&3]).len(), 5);
+
+        let result = bfs([0, 1], neighbours, |_| false);
+        assert_eq!(result.distance(&1), Some(0));
+        assert_eq!(result.distance(&3), Some(1));
+    }
+}
@@ -0,0 +1,205 @@
+use std::fmt;
+use std::num::TryFromIntError;
+use std::time::{Duration, Instant};
+
+use crate::parse::ParseError;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Part {
+    One,
+    Two,
+}
+
+impl Part {
+    pub fn number(self) -> u32 {
+        match self {
+            Part::One => 1,
+            Part::Two => 2,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Answer {
+    Number(u64),
+    Text(String),
+}
+
+impl fmt::Display for Answer {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Answer::Number(number) => write!(f, "{number}"),
+            Answer::Text(text) => write!(f, "{text}"),
+        }
+    }
+}
+
+impl From<u32> for Answer {
+    fn from(value: u32) -> Self {
+        Answer::Number(value.into())
+    }
+}
+
+impl From<u64> for Answer {
+    fn from(value: u64) -> Self {
+        Answer::Number(value)
+    }
+}
+
+impl From<usize> for Answer {
+    fn from(value: usize) -> Self {
+        Answer::Number(value as u64)
+    }
+}
+
+/// Fails for negative numbers, which are no answer.
+impl TryFrom<i64> for Answer {
+    type Error = TryFromIntError;
+
+    fn try_from(value: i64) -> Result<Self, Self::Error> {
+        u64::try_from(value).map(Answer::Number)
+    }
+}
+
+impl From<String> for Answer {
+    fn from(value: String) -> Self {
+        Answer::Text(value)
+    }
+}
+
+/// An example from the puzzle description with the answers given for it,
+/// `None` for a part the example does not cover.
+#[derive(Debug)]
+pub struct Example {
+    pub input: &'static str,
+    pub part1: Option<&'static str>,
+    pub part2: Option<&'static str>,
+}
+
+/// A puzzle day: the input is parsed once and shared by both parts.
+///
+/// Parts that are not solved (yet) keep the default implementation and
+/// return `None`. Parts that can fail on an input that parses override
+/// `try_part1` or `try_part2` instead and return why.
+pub trait Solution {
+    type Input;
+
+    /// The examples `aoc24 run --example` checks the day against.
+    const EXAMPLES: &'static [Example] = &[];
+
+    fn parse(input: &str) -> Result<Self::Input, ParseError>;
+
+    /// Parses one of `EXAMPLES`, days whose examples use other settings than
+    /// the puzzle input, e.g. a smaller grid, override it to use those.
+    fn parse_example(input: &str) -> Result<Self::Input, ParseError> {
+        Self::parse(input)
+    }
+
+    fn part1(_input: &Self::Input) -> Option<Answer> {
+        None
+    }
+
+    fn part2(_input: &Self::Input) -> Option<Answer> {
+        None
+    }
+
+    fn try_part1(input: &Self::Input) -> Result<Option<Answer>, String> {
+        Ok(Self::part1(input))
+    }
+
+    fn try_part2(input: &Self::Input) -> Result<Option<Answer>, String> {
+        Ok(Self::part2(input))
+    }
+}
+
+/// The answer of one part, or why it could not be solved, and how long it
+/// took.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PartResult {
+    pub part: Part,
+    pub answer: Result<Option<Answer>, String>,
+    pub duration: Duration,
+}
+
+pub fn run<S: Solution>(input: &str, parts: &[Part]) -> Result<Vec<PartResult>, ParseError> {
+    Ok(solve_parts::<S>(&S::parse(input)?, parts))
+}
+
+/// Like `run`, for one of the day's examples.
+pub fn run_example<S: Solution>(
+    input: &str,
+    parts: &[Part],
+) -> Result<Vec<PartResult>, ParseError> {
+    Ok(solve_parts::<S>(&S::parse_example(input)?, parts))
+}
+
+fn solve_parts<S: Solution>(input: &S::Input, parts: &[Part]) -> Vec<PartResult> {
+    parts
+        .iter()
+        .map(|&part| {
+            let start = Instant::now();
+            let answer = match part {
+                Part::One => S::try_part1(input),
+                Part::Two => S::try_part2(input),
+            };
+            PartResult {
+                part,
+                answer,
+                duration: start.elapsed(),
+            }
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    struct Example;
+
+    impl Solution for Example {
+        type Input = Vec<u32>;
+
+        fn parse(input: &str) -> Result<Self::Input, ParseError> {
+            input
+                .split(',')
+                .map(|x| crate::parse::number(input, x))
+                .collect()
+        }
+
+        fn part1(input: &Self::Input) -> Option<Answer> {
+            Some(input.iter().sum::<u32>().into())
+        }
+
+        fn try_part2(input: &Self::Input) -> Result<Option<Answer>, String> {
+            match input.iter().max() {
+                Some(&max) if max > 9 => Err(format!("{max} has more than one digit")),
+                _ => Ok(None),
+            }
+        }
+    }
+
+    #[test]
+    fn test_run() {
+        let results = run::<Example>("1,2,3", &[Part::One, Part::Two]).unwrap();
+        let answers: Vec<_> = results
+            .into_iter()
+            .map(|result| (result.part, result.answer))
+            .collect();
+        assert_eq!(
+            answers,
+            [(Part::One, Ok(Some(Answer::Number(6)))), (Part::Two, Ok(None))]
+        );
+        let results = run::<Example>("1,20", &[Part::Two]).unwrap();
+        assert_eq!(results[0].answer, Err("20 has more than one digit".to_string()));
+        assert_eq!(run::<Example>("1,x", &[Part::One]).unwrap_err().column, 3);
+    }
+
+    #[test]
+    fn test_display() {
+        assert_eq!(Answer::from(42usize).to_string(), "42");
+        assert_eq!(Answer::try_from(42i64), Ok(Answer::Number(42)));
+        assert!(Answer::try_from(-1i64).is_err());
+        assert_eq!(Answer::from("co,de".to_string()).to_string(), "co,de");
+    }
+}